@@ -0,0 +1,256 @@
+// Redistributes food items across elves so the heaviest load is as small as
+// possible (multiway number partitioning).
+
+const EXACT_LIMIT: usize = 20;
+const LOCAL_SEARCH_ROUNDS: usize = 10000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub calories: u64,
+    pub from: usize,
+    pub to: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Plan {
+    pub moves: Vec<Move>,
+    pub before: Vec<u64>,
+    pub after: Vec<u64>,
+    pub lower_bound: u64,
+    pub exact: bool,
+}
+
+impl Plan {
+    pub fn max_load(&self) -> u64 {
+        self.after.iter().copied().max().unwrap_or(0)
+    }
+
+    pub fn imbalance(&self) -> u64 {
+        self.max_load() - self.after.iter().copied().min().unwrap_or(0)
+    }
+}
+
+struct Item {
+    calories: u64,
+    owner: usize,
+}
+
+fn loads(items: &[Item], bins: &[usize], k: usize) -> Vec<u64> {
+    let mut loads = vec![0; k];
+    for (item, &bin) in items.iter().zip(bins) {
+        loads[bin] += item.calories;
+    }
+    loads
+}
+
+// Longest processing time first: biggest item goes to the lightest elf.
+fn lpt(items: &[Item], order: &[usize], k: usize) -> Vec<usize> {
+    let mut loads = vec![0u64; k];
+    let mut bins = vec![0; items.len()];
+    for &i in order {
+        let bin = (0..k).min_by_key(|&b| (loads[b], b)).unwrap();
+        loads[bin] += items[i].calories;
+        bins[i] = bin;
+    }
+    bins
+}
+
+fn branch_and_bound(
+    items: &[Item],
+    order: &[usize],
+    depth: usize,
+    loads: &mut Vec<u64>,
+    bins: &mut Vec<usize>,
+    best: &mut (u64, Vec<usize>),
+    lower_bound: u64,
+) {
+    if best.0 == lower_bound {
+        return;
+    }
+    if depth == order.len() {
+        let max = loads.iter().copied().max().unwrap_or(0);
+        if max < best.0 {
+            *best = (max, bins.clone());
+        }
+        return;
+    }
+
+    let i = order[depth];
+    let calories = items[i].calories;
+    for bin in 0..loads.len() {
+        // Elves with the same load are interchangeable, only try the first one.
+        if loads[..bin].contains(&loads[bin]) || loads[bin] + calories >= best.0 {
+            continue;
+        }
+        loads[bin] += calories;
+        bins[i] = bin;
+        branch_and_bound(items, order, depth + 1, loads, bins, best, lower_bound);
+        loads[bin] -= calories;
+    }
+}
+
+// Moves an item, or swaps two, out of the heaviest elf whenever the lighter elf
+// stays below what the heavy one carried.
+fn local_search(items: &[Item], bins: &mut [usize], k: usize) {
+    let mut loads = loads(items, bins, k);
+    let mut carried = vec![Vec::new(); k];
+    for (i, &bin) in bins.iter().enumerate() {
+        carried[bin].push(i);
+    }
+
+    for _ in 0..LOCAL_SEARCH_ROUNDS {
+        let heavy = (0..k)
+            .max_by_key(|&b| (loads[b], std::cmp::Reverse(b)))
+            .unwrap();
+
+        let mut lighter = (0..k)
+            .filter(|&b| loads[b] < loads[heavy])
+            .collect::<Vec<_>>();
+        lighter.sort_by_key(|&b| loads[b]);
+
+        let improvement = lighter.iter().find_map(|&light| {
+            let gap = loads[heavy] - loads[light];
+            let single = carried[heavy]
+                .iter()
+                .find(|&&a| items[a].calories < gap)
+                .map(|&a| (light, a, None));
+            single.or_else(|| {
+                carried[heavy].iter().find_map(|&a| {
+                    carried[light]
+                        .iter()
+                        .find(|&&b| {
+                            items[b].calories < items[a].calories
+                                && items[a].calories - items[b].calories < gap
+                        })
+                        .map(|&b| (light, a, Some(b)))
+                })
+            })
+        });
+
+        let (light, a, b) = match improvement {
+            Some(improvement) => improvement,
+            None => break,
+        };
+        loads[heavy] -= items[a].calories;
+        loads[light] += items[a].calories;
+        bins[a] = light;
+        carried[heavy].retain(|&i| i != a);
+        carried[light].push(a);
+        if let Some(b) = b {
+            loads[light] -= items[b].calories;
+            loads[heavy] += items[b].calories;
+            bins[b] = heavy;
+            carried[light].retain(|&i| i != b);
+            carried[heavy].push(b);
+        }
+    }
+}
+
+// The solver numbers bins freely; map them back onto elves so that as many items
+// as possible stay where they are.
+fn relabel(items: &[Item], bins: &mut [usize], k: usize) {
+    let mut overlap = vec![vec![0u64; k]; k];
+    for (item, &bin) in items.iter().zip(bins.iter()) {
+        overlap[bin][item.owner] += 1;
+    }
+
+    let mut pairs = (0..k)
+        .flat_map(|bin| (0..k).map(move |elf| (bin, elf)))
+        .collect::<Vec<_>>();
+    pairs.sort_by_key(|&(bin, elf)| (std::cmp::Reverse(overlap[bin][elf]), bin, elf));
+
+    let mut mapping = vec![usize::MAX; k];
+    let mut taken = vec![false; k];
+    for (bin, elf) in pairs {
+        if mapping[bin] == usize::MAX && !taken[elf] {
+            mapping[bin] = elf;
+            taken[elf] = true;
+        }
+    }
+
+    for bin in bins.iter_mut() {
+        *bin = mapping[*bin];
+    }
+}
+
+pub fn balance(elves: &[Vec<u64>]) -> Plan {
+    let k = elves.len();
+    let items = elves
+        .iter()
+        .enumerate()
+        .flat_map(|(owner, list)| list.iter().map(move |&calories| Item { calories, owner }))
+        .collect::<Vec<_>>();
+
+    let before = loads(
+        &items,
+        &items.iter().map(|i| i.owner).collect::<Vec<_>>(),
+        k,
+    );
+    let total = items.iter().map(|i| i.calories).sum::<u64>();
+    let largest = items.iter().map(|i| i.calories).max().unwrap_or(0);
+    let lower_bound = if k == 0 {
+        0
+    } else {
+        largest.max(total.div_ceil(k as u64))
+    };
+
+    let mut order = (0..items.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| std::cmp::Reverse(items[i].calories));
+
+    let exact = items.len() <= EXACT_LIMIT;
+    let mut bins = lpt(&items, &order, k);
+    if exact {
+        let start = loads(&items, &bins, k).into_iter().max().unwrap_or(0);
+        let mut best = (start, bins.clone());
+        let mut scratch = bins.clone();
+        branch_and_bound(
+            &items,
+            &order,
+            0,
+            &mut vec![0; k],
+            &mut scratch,
+            &mut best,
+            lower_bound,
+        );
+        bins = best.1;
+        relabel(&items, &mut bins, k);
+    } else {
+        local_search(&items, &mut bins, k);
+        relabel(&items, &mut bins, k);
+
+        // Improving the current assignment in place usually needs far fewer moves.
+        let mut in_place = items.iter().map(|i| i.owner).collect::<Vec<_>>();
+        local_search(&items, &mut in_place, k);
+        let cost = |bins: &[usize]| {
+            let max = loads(&items, bins, k).into_iter().max().unwrap_or(0);
+            let moves = items
+                .iter()
+                .zip(bins)
+                .filter(|(item, &bin)| item.owner != bin)
+                .count();
+            (max, moves)
+        };
+        if cost(&in_place) <= cost(&bins) {
+            bins = in_place;
+        }
+    }
+
+    let moves = items
+        .iter()
+        .zip(bins.iter())
+        .filter(|(item, &bin)| item.owner != bin)
+        .map(|(item, &bin)| Move {
+            calories: item.calories,
+            from: item.owner,
+            to: bin,
+        })
+        .collect();
+
+    Plan {
+        moves,
+        before,
+        after: loads(&items, &bins, k),
+        lower_bound,
+        exact,
+    }
+}
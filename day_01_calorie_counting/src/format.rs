@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FoodItem {
+    pub label: Option<String>,
+    pub calories: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Elf {
+    pub name: Option<String>,
+    pub items: Vec<FoodItem>,
+}
+
+impl Elf {
+    pub fn calories(&self) -> Vec<u64> {
+        self.items.iter().map(|i| i.calories).collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputFormat {
+    // Blank-line separated numbers, the puzzle's own format.
+    Plain,
+    // Plain plus `Elf Alice:` headers, `apple 3000` labels and `#` comments.
+    Named,
+    // `elf,item,calories` rows, with an optional header row.
+    Csv,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn is_comment(line: &str) -> bool {
+    line.starts_with('#') || line.starts_with("//")
+}
+
+// Splits a CSV row on commas outside double quotes, where `""` is a literal quote.
+fn split_csv(line: &str) -> Option<Vec<String>> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        let field = fields.last_mut().unwrap();
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            ',' if !quoted => fields.push(String::new()),
+            c => field.push(c),
+        }
+    }
+    (!quoted).then_some(fields)
+}
+
+// A CSV row has two or three fields and ends in a calorie count, or is the header.
+fn is_csv_row(line: &str) -> bool {
+    match split_csv(line) {
+        Some(fields) if (2..=3).contains(&fields.len()) => {
+            let last = fields.last().unwrap().trim();
+            last.parse::<u64>().is_ok() || last.eq_ignore_ascii_case("calories")
+        }
+        _ => false,
+    }
+}
+
+pub fn detect_format(input: &str) -> InputFormat {
+    let mut lines = input.lines().map(str::trim).filter(|line| !line.is_empty());
+
+    match lines.clone().find(|line| !is_comment(line)) {
+        Some(line) if is_csv_row(line) => InputFormat::Csv,
+        _ if lines.all(|line| line.parse::<u64>().is_ok()) => InputFormat::Plain,
+        _ => InputFormat::Named,
+    }
+}
+
+fn parse_calories(s: &str, line: usize) -> Result<u64, ParseError> {
+    s.trim().parse::<u64>().map_err(|_| ParseError {
+        line,
+        message: format!("expected a calorie count, found {:?}", s.trim()),
+    })
+}
+
+fn parse_header(line: &str) -> Option<String> {
+    let name = line.strip_suffix(':')?.trim();
+    let name = name.strip_prefix("Elf ").unwrap_or(name).trim();
+    Some(name.to_string())
+}
+
+fn parse_blocks(input: &str) -> Result<Vec<Elf>, ParseError> {
+    let mut elves = Vec::new();
+    let mut elf: Option<Elf> = None;
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            elves.extend(elf.take());
+        } else if is_comment(line) {
+            continue;
+        } else if let Some(name) = parse_header(line) {
+            elves.extend(elf.take());
+            elf = Some(Elf {
+                name: Some(name),
+                items: vec![],
+            });
+        } else {
+            let (label, calories) = match line.rsplit_once(char::is_whitespace) {
+                Some((label, calories)) => (Some(label.trim().to_string()), calories),
+                None => (None, line),
+            };
+            let calories = parse_calories(calories, i + 1)?;
+            elf.get_or_insert_with(Elf::default)
+                .items
+                .push(FoodItem { label, calories });
+        }
+    }
+    elves.extend(elf);
+
+    Ok(elves)
+}
+
+fn parse_csv(input: &str) -> Result<Vec<Elf>, ParseError> {
+    let mut elves: Vec<Elf> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut header_seen = false;
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || is_comment(line) {
+            continue;
+        }
+
+        let fields = split_csv(line).ok_or_else(|| ParseError {
+            line: i + 1,
+            message: "unterminated quoted field".to_string(),
+        })?;
+        let fields = fields.iter().map(|f| f.trim()).collect::<Vec<_>>();
+        let (name, label, calories) = match fields[..] {
+            [name, label, calories] => (name, Some(label), calories),
+            [name, calories] => (name, None, calories),
+            _ => {
+                return Err(ParseError {
+                    line: i + 1,
+                    message: format!("expected elf,item,calories, found {} fields", fields.len()),
+                })
+            }
+        };
+        if !header_seen && elves.is_empty() && calories.parse::<u64>().is_err() {
+            header_seen = true;
+            continue;
+        }
+
+        let calories = parse_calories(calories, i + 1)?;
+        let label = label.filter(|l| !l.is_empty()).map(str::to_string);
+        let item = FoodItem { label, calories };
+        match positions.get(name) {
+            Some(&j) => elves[j].items.push(item),
+            None => {
+                positions.insert(name.to_string(), elves.len());
+                elves.push(Elf {
+                    name: Some(name.to_string()),
+                    items: vec![item],
+                });
+            }
+        }
+    }
+
+    Ok(elves)
+}
+
+pub fn parse_inventory(input: &str) -> Result<Vec<Elf>, ParseError> {
+    match detect_format(input) {
+        InputFormat::Csv => parse_csv(input),
+        InputFormat::Plain | InputFormat::Named => parse_blocks(input),
+    }
+}
@@ -1,30 +1,59 @@
-fn sum_calories(calories: &str) -> u32 {
-    calories
-        .lines()
-        .map(|c| c.parse::<u32>().unwrap())
-        .sum()
+mod balance;
+mod format;
+mod report;
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+pub use crate::balance::{balance, Move, Plan};
+pub use crate::format::{detect_format, parse_inventory, Elf, FoodItem, InputFormat, ParseError};
+pub use crate::report::{report, ElfStats, Format, Report, ReportError, ReportOptions};
+
+fn sum_calories(calories: &[u64]) -> u64 {
+    calories.iter().sum()
+}
+
+fn inventory(input: &str) -> Vec<Elf> {
+    parse_inventory(input).unwrap_or_else(|e| panic!("invalid inventory: {e}"))
 }
 
-pub fn part_one(input: &str) -> u32 {
-    input
-        .split("\n\n")
-        .map(sum_calories)
-        .max()
-        .unwrap()
+pub fn parse_elves(input: &str) -> Vec<Vec<u64>> {
+    inventory(input).iter().map(Elf::calories).collect()
 }
 
-pub fn part_two(input: &str) -> u32 {
-    let mut calories: Vec<u32> = input
-        .split("\n\n")
-        .map(sum_calories)
-        .collect();
-    calories.sort_by(|a, b| b.cmp(a));
-    calories.iter().take(3).sum()
+// Returns the k elves carrying the most calories as (elf_index, total), largest first.
+// Elves with equal totals are ordered by their index.
+pub fn top_k_elves(input: &str, k: usize) -> Vec<(usize, u64)> {
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    for (index, total) in parse_elves(input)
+        .iter()
+        .map(|c| sum_calories(c))
+        .enumerate()
+    {
+        heap.push(Reverse((total, Reverse(index))));
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((total, Reverse(index)))| (index, total))
+        .collect()
+}
+
+pub fn part_one(input: &str) -> u64 {
+    top_k_elves(input, 1).iter().map(|&(_, total)| total).sum()
+}
+
+pub fn part_two(input: &str) -> u64 {
+    top_k_elves(input, 3).iter().map(|&(_, total)| total).sum()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::BTreeMap;
 
     const EXAMPLE: &str = include_str!("../data/example.txt");
 
@@ -33,4 +62,205 @@ mod tests {
         assert_eq!(part_one(EXAMPLE), 24000);
         assert_eq!(part_two(EXAMPLE), 45000);
     }
+
+    #[test]
+    fn top_k_elves_works() {
+        assert_eq!(
+            top_k_elves(EXAMPLE, 3),
+            vec![(3, 24000), (2, 11000), (4, 10000)]
+        );
+        assert_eq!(top_k_elves("5\n\n7\n\n5", 2), vec![(1, 7), (0, 5)]);
+        assert_eq!(top_k_elves("4294967295\n1", 1), vec![(0, 4294967296)]);
+    }
+
+    #[test]
+    fn report_works() {
+        let options = ReportOptions {
+            percentiles: vec![25, 50, 90],
+            ..Default::default()
+        };
+        let r = report(EXAMPLE, &options).unwrap();
+        assert_eq!(r.elves[0].items, 3);
+        assert_eq!(r.elves[0].mean, 2000.0);
+        assert_eq!(r.elves[2].largest, 6000);
+        assert_eq!(r.median, 10000.0);
+        assert_eq!(r.percentiles, vec![(25, 6000), (50, 10000), (90, 24000)]);
+        assert_eq!(
+            r.histogram,
+            BTreeMap::from([(0, 2), (10000, 2), (20000, 1)])
+        );
+        assert!(r.elves.iter().all(|e| !e.outlier));
+
+        let options = ReportOptions {
+            z_threshold: 1.5,
+            ..Default::default()
+        };
+        let r = report(EXAMPLE, &options).unwrap();
+        assert_eq!(
+            r.elves
+                .iter()
+                .filter(|e| e.outlier)
+                .map(|e| e.index)
+                .collect::<Vec<_>>(),
+            vec![3]
+        );
+
+        let csv = r.render(Format::Csv);
+        let sections = csv.split("\n\n").collect::<Vec<_>>();
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[0].lines().count(), 6);
+        assert!(sections[0]
+            .lines()
+            .nth(4)
+            .unwrap()
+            .starts_with("3,,3,24000,8000,9000,"));
+        assert_eq!(
+            sections[1],
+            "statistic,value\nmedian,10000\np25,6000\np50,10000\np75,11000\np90,24000\np99,24000"
+        );
+        assert_eq!(
+            sections[2],
+            "bucket_start,bucket_end,count\n0,9999,2\n10000,19999,2\n20000,29999,1\n"
+        );
+
+        let options = ReportOptions {
+            bucket_size: 1,
+            ..Default::default()
+        };
+        let sparse = report("1\n\n1000000000000\n", &options).unwrap();
+        assert_eq!(
+            sparse.histogram,
+            BTreeMap::from([(1, 1), (1000000000000, 1)])
+        );
+        assert!(r
+            .render(Format::Json)
+            .starts_with("{\"elves\":[{\"index\":0,\"name\":null,\"items\":3,\"total\":6000,"));
+
+        assert_eq!("csv".parse(), Ok(Format::Csv));
+        assert_eq!(
+            "jsn".parse::<Format>(),
+            Err(ReportError::UnknownFormat("jsn".to_string()))
+        );
+        let options = ReportOptions {
+            bucket_size: 0,
+            ..Default::default()
+        };
+        assert_eq!(report(EXAMPLE, &options), Err(ReportError::ZeroBucketSize));
+    }
+
+    // Carries out the plan's moves item by item and checks they end at `after`.
+    fn replay(elves: &[Vec<u64>], plan: &Plan) {
+        let mut elves = elves.to_vec();
+        for m in &plan.moves {
+            let i = elves[m.from]
+                .iter()
+                .position(|&c| c == m.calories)
+                .expect("moved item is missing");
+            elves[m.from].swap_remove(i);
+            elves[m.to].push(m.calories);
+        }
+        let loads = elves
+            .iter()
+            .map(|e| e.iter().sum::<u64>())
+            .collect::<Vec<_>>();
+        assert_eq!(loads, plan.after);
+    }
+
+    #[test]
+    fn balance_works() {
+        let plan = balance(&parse_elves(EXAMPLE));
+        replay(&parse_elves(EXAMPLE), &plan);
+        assert!(plan.exact);
+        assert_eq!(plan.before, vec![6000, 4000, 11000, 24000, 10000]);
+        assert_eq!(plan.lower_bound, 11000);
+        assert_eq!(plan.max_load(), 11000);
+        assert_eq!(plan.after.iter().sum::<u64>(), 55000);
+        assert!(plan.moves.iter().all(|m| m.from != m.to));
+
+        let elves = (0..40)
+            .map(|i| vec![1000 + i * 37, 500, 3000 - i * 11])
+            .collect::<Vec<_>>();
+        let plan = balance(&elves);
+        replay(&elves, &plan);
+        assert!(!plan.exact);
+        assert!(
+            plan.imbalance()
+                <= plan.before.iter().max().unwrap() - plan.before.iter().min().unwrap()
+        );
+        assert!(plan.max_load() < *plan.before.iter().max().unwrap());
+    }
+
+    #[test]
+    fn formats_work() {
+        const NAMED: &str = "# the example, with names\nElf Alice:\napple 1000\nbread 2000\n3000\n\nElf Bob:\n4000\n\nElf Carol:\n5000\n6000\nElf Dave:\n7000\n8000\n9000\n\n10000\n";
+        const CSV: &str = "elf,item,calories\nAlice,apple,1000\nAlice,bread,2000\nAlice,,3000\nBob,,4000\nCarol,,5000\nCarol,,6000\nDave,,7000\nDave,,8000\nDave,,9000\nErin,,10000\n";
+
+        assert_eq!(detect_format(EXAMPLE), InputFormat::Plain);
+        assert_eq!(detect_format(NAMED), InputFormat::Named);
+        assert_eq!(detect_format(CSV), InputFormat::Csv);
+
+        for input in [EXAMPLE, NAMED, CSV] {
+            assert_eq!(parse_elves(input), parse_elves(EXAMPLE));
+            assert_eq!(part_one(input), 24000);
+            assert_eq!(part_two(input), 45000);
+        }
+
+        let elves = parse_inventory(NAMED).unwrap();
+        assert_eq!(elves[0].name.as_deref(), Some("Alice"));
+        assert_eq!(
+            elves[0].items[1],
+            FoodItem {
+                label: Some("bread".to_string()),
+                calories: 2000
+            }
+        );
+        assert_eq!(elves[4].name, None);
+        assert_eq!(
+            parse_inventory(CSV).unwrap()[4].name.as_deref(),
+            Some("Erin")
+        );
+
+        assert_eq!(
+            parse_inventory("1000\nlots\n").unwrap_err(),
+            ParseError {
+                line: 2,
+                message: "expected a calorie count, found \"lots\"".to_string()
+            }
+        );
+        assert_eq!(
+            parse_inventory("elf,item,calories\nAlice,1,2,3\n")
+                .unwrap_err()
+                .line,
+            2
+        );
+
+        // Commas inside named labels and quoted CSV fields.
+        let named = "Elf Smith, Jr.:\napple, red 3000\n";
+        assert_eq!(detect_format(named), InputFormat::Named);
+        assert_eq!(
+            parse_inventory(named).unwrap()[0].items[0].label.as_deref(),
+            Some("apple, red")
+        );
+        let quoted = "\"Smith, Jr.\",\"the \"\"good\"\" pie\",500\n\"Smith, Jr.\",,200\n";
+        assert_eq!(detect_format(quoted), InputFormat::Csv);
+        let elves = parse_inventory(quoted).unwrap();
+        assert_eq!(elves.len(), 1);
+        assert_eq!(elves[0].name.as_deref(), Some("Smith, Jr."));
+        assert_eq!(elves[0].items[0].label.as_deref(), Some("the \"good\" pie"));
+        assert_eq!(
+            parse_inventory("elf,item,calories\n\"Ann,1\n")
+                .unwrap_err()
+                .message,
+            "unterminated quoted field"
+        );
+    }
+
+    #[test]
+    fn report_escapes_names() {
+        let r = report("Elf \"Tab\tby\u{1}\":\n100\n", &ReportOptions::default()).unwrap();
+        assert!(r
+            .render(Format::Json)
+            .contains("\"name\":\"\\\"Tab\\tby\\u0001\\\"\""));
+        assert!(r.render(Format::Csv).contains(",\"\"\"Tab\tby\u{1}\"\"\","));
+    }
 }
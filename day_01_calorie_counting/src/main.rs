@@ -1,8 +1,85 @@
-use day_01_calorie_counting::{part_one, part_two};
+use day_01_calorie_counting::{
+    balance, parse_elves, part_one, part_two, report, Format, ReportOptions,
+};
 
 const INPUT: &str = include_str!("../data/input.txt");
 
+const REPORT_USAGE: &str =
+    "usage: report [table|json|csv] [BUCKET_SIZE] [--percentiles P,P,...] [--z-threshold Z]";
+
+fn report_args(args: &[String]) -> Result<(Format, ReportOptions), String> {
+    let mut format = Format::Table;
+    let mut options = ReportOptions::default();
+    let mut positional = 0;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--percentiles" => {
+                let value = args.next().ok_or("--percentiles needs a value")?;
+                options.percentiles = value
+                    .split(',')
+                    .map(|p| match p.trim().parse::<u32>() {
+                        Ok(p) if p <= 100 => Ok(p),
+                        _ => Err(format!("invalid percentile {p:?}")),
+                    })
+                    .collect::<Result<_, _>>()?;
+            }
+            "--z-threshold" => {
+                let value = args.next().ok_or("--z-threshold needs a value")?;
+                options.z_threshold = match value.parse::<f64>() {
+                    Ok(z) if z.is_finite() && z >= 0.0 => z,
+                    _ => return Err(format!("invalid z threshold {value:?}")),
+                };
+            }
+            _ if positional == 0 => {
+                format = arg.parse().map_err(|e| format!("{e}"))?;
+                positional += 1;
+            }
+            _ if positional == 1 => {
+                options.bucket_size = arg
+                    .parse()
+                    .map_err(|_| format!("invalid bucket size {arg:?}"))?;
+                positional += 1;
+            }
+            _ => return Err(format!("unexpected argument {arg:?}")),
+        }
+    }
+    Ok((format, options))
+}
+
 fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.get(1).map(String::as_str) == Some("report") {
+        let (format, options) = report_args(&args[2..]).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            eprintln!("{REPORT_USAGE}");
+            std::process::exit(1);
+        });
+        match report(INPUT, &options) {
+            Ok(report) => print!("{}", report.render(format)),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("balance") {
+        let plan = balance(&parse_elves(INPUT));
+        for m in &plan.moves {
+            println!("move {} from elf {} to elf {}", m.calories, m.from, m.to);
+        }
+        println!(
+            "max load: {} -> {} (lower bound {}{})",
+            plan.before.iter().max().unwrap(),
+            plan.max_load(),
+            plan.lower_bound,
+            if plan.exact { ", exact" } else { "" }
+        );
+        return;
+    }
+
     println!("part one answer: {}", part_one(INPUT));
     println!("part two answer: {}", part_two(INPUT));
 }
@@ -0,0 +1,315 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use crate::{inventory, sum_calories, Elf};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = ReportError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(ReportError::UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReportError {
+    UnknownFormat(String),
+    ZeroBucketSize,
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportError::UnknownFormat(s) => {
+                write!(f, "unknown format {s:?}, expected table, json or csv")
+            }
+            ReportError::ZeroBucketSize => write!(f, "bucket size must be positive"),
+        }
+    }
+}
+
+impl std::error::Error for ReportError {}
+
+#[derive(Clone, Debug)]
+pub struct ReportOptions {
+    pub bucket_size: u64,
+    pub percentiles: Vec<u32>,
+    pub z_threshold: f64,
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        Self {
+            bucket_size: 10000,
+            percentiles: vec![25, 50, 75, 90, 99],
+            z_threshold: 2.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElfStats {
+    pub index: usize,
+    pub name: Option<String>,
+    pub items: usize,
+    pub total: u64,
+    pub mean: f64,
+    pub largest: u64,
+    pub z_score: f64,
+    pub outlier: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub elves: Vec<ElfStats>,
+    pub median: f64,
+    pub percentiles: Vec<(u32, u64)>,
+    // Bucket start -> number of elves whose total falls in [start, start + bucket_size).
+    // Empty buckets are left out.
+    pub histogram: BTreeMap<u64, usize>,
+    pub bucket_size: u64,
+}
+
+fn elf_stats(index: usize, elf: &Elf) -> ElfStats {
+    let items = elf.calories();
+    let total = sum_calories(&items);
+    let mean = if items.is_empty() {
+        0.0
+    } else {
+        total as f64 / items.len() as f64
+    };
+
+    ElfStats {
+        index,
+        name: elf.name.clone(),
+        items: items.len(),
+        total,
+        mean,
+        largest: items.iter().copied().max().unwrap_or(0),
+        z_score: 0.0,
+        outlier: false,
+    }
+}
+
+fn json_string(s: Option<&str>) -> String {
+    let Some(s) = s else {
+        return "null".to_string();
+    };
+    let mut json = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn csv_field(s: Option<&str>) -> String {
+    match s {
+        Some(s) if s.contains([',', '"', '\n', '\r']) => format!("\"{}\"", s.replace('"', "\"\"")),
+        Some(s) => s.to_string(),
+        None => String::new(),
+    }
+}
+
+fn median(sorted: &[u64]) -> f64 {
+    let n = sorted.len();
+    if n == 0 {
+        0.0
+    } else if n % 2 == 1 {
+        sorted[n / 2] as f64
+    } else {
+        (sorted[n / 2 - 1] + sorted[n / 2]) as f64 / 2.0
+    }
+}
+
+// Nearest-rank percentile.
+fn percentile(sorted: &[u64], p: u32) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (p as usize * sorted.len()).div_ceil(100).max(1);
+    sorted[rank.min(sorted.len()) - 1]
+}
+
+fn histogram(totals: &[u64], bucket_size: u64) -> BTreeMap<u64, usize> {
+    let mut buckets = BTreeMap::new();
+    for total in totals {
+        *buckets.entry(total - total % bucket_size).or_default() += 1;
+    }
+    buckets
+}
+
+pub fn report(input: &str, options: &ReportOptions) -> Result<Report, ReportError> {
+    if options.bucket_size == 0 {
+        return Err(ReportError::ZeroBucketSize);
+    }
+
+    let mut elves = inventory(input)
+        .iter()
+        .enumerate()
+        .map(|(i, elf)| elf_stats(i, elf))
+        .collect::<Vec<_>>();
+
+    let n = elves.len() as f64;
+    let mean = elves.iter().map(|e| e.total as f64).sum::<f64>() / n;
+    let std_dev = (elves
+        .iter()
+        .map(|e| (e.total as f64 - mean).powi(2))
+        .sum::<f64>()
+        / n)
+        .sqrt();
+    for elf in elves.iter_mut() {
+        if std_dev > 0.0 {
+            elf.z_score = (elf.total as f64 - mean) / std_dev;
+        }
+        elf.outlier = elf.z_score.abs() >= options.z_threshold;
+    }
+
+    let mut totals = elves.iter().map(|e| e.total).collect::<Vec<_>>();
+    totals.sort();
+
+    Ok(Report {
+        median: median(&totals),
+        percentiles: options
+            .percentiles
+            .iter()
+            .map(|&p| (p, percentile(&totals, p)))
+            .collect(),
+        histogram: histogram(&totals, options.bucket_size),
+        bucket_size: options.bucket_size,
+        elves,
+    })
+}
+
+impl Report {
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Table => self.to_table(),
+            Format::Json => self.to_json(),
+            Format::Csv => self.to_csv(),
+        }
+    }
+
+    fn to_table(&self) -> String {
+        let mut s = String::new();
+        writeln!(
+            s,
+            "{:>5} {:<12} {:>6} {:>10} {:>10} {:>10} {:>7}",
+            "elf", "name", "items", "total", "mean", "largest", "z"
+        )
+        .unwrap();
+        for e in &self.elves {
+            let flag = if e.outlier { " *" } else { "" };
+            writeln!(
+                s,
+                "{:>5} {:<12} {:>6} {:>10} {:>10.1} {:>10} {:>7.2}{flag}",
+                e.index,
+                e.name.as_deref().unwrap_or("-"),
+                e.items,
+                e.total,
+                e.mean,
+                e.largest,
+                e.z_score
+            )
+            .unwrap();
+        }
+        writeln!(s, "median: {:.1}", self.median).unwrap();
+        for (p, v) in &self.percentiles {
+            writeln!(s, "p{p}: {v}").unwrap();
+        }
+        writeln!(s, "histogram:").unwrap();
+        for (start, count) in &self.histogram {
+            writeln!(
+                s,
+                "{:>10}-{:<10} {}",
+                start,
+                start.saturating_add(self.bucket_size - 1),
+                "#".repeat(*count)
+            )
+            .unwrap();
+        }
+        s
+    }
+
+    fn to_json(&self) -> String {
+        let elves = self.elves
+            .iter()
+            .map(|e| format!(
+                "{{\"index\":{},\"name\":{},\"items\":{},\"total\":{},\"mean\":{},\"largest\":{},\"z_score\":{},\"outlier\":{}}}",
+                e.index, json_string(e.name.as_deref()), e.items, e.total, e.mean, e.largest, e.z_score, e.outlier))
+            .collect::<Vec<_>>()
+            .join(",");
+        let percentiles = self
+            .percentiles
+            .iter()
+            .map(|(p, v)| format!("\"p{p}\":{v}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let histogram = self
+            .histogram
+            .iter()
+            .map(|(start, count)| format!("{{\"start\":{start},\"count\":{count}}}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "{{\"elves\":[{elves}],\"median\":{},\"percentiles\":{{{percentiles}}},\"bucket_size\":{},\"histogram\":[{histogram}]}}",
+            self.median, self.bucket_size)
+    }
+
+    fn to_csv(&self) -> String {
+        let mut s = String::from("elf,name,items,total,mean,largest,z_score,outlier\n");
+        for e in &self.elves {
+            writeln!(
+                s,
+                "{},{},{},{},{},{},{},{}",
+                e.index,
+                csv_field(e.name.as_deref()),
+                e.items,
+                e.total,
+                e.mean,
+                e.largest,
+                e.z_score,
+                e.outlier
+            )
+            .unwrap();
+        }
+
+        writeln!(s, "\nstatistic,value").unwrap();
+        writeln!(s, "median,{}", self.median).unwrap();
+        for (p, v) in &self.percentiles {
+            writeln!(s, "p{p},{v}").unwrap();
+        }
+
+        writeln!(s, "\nbucket_start,bucket_end,count").unwrap();
+        for (start, count) in &self.histogram {
+            writeln!(
+                s,
+                "{start},{},{count}",
+                start.saturating_add(self.bucket_size - 1)
+            )
+            .unwrap();
+        }
+        s
+    }
+}
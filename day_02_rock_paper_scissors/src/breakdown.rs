@@ -0,0 +1,149 @@
+// Per-round scores under both readings of the strategy guide.
+
+use std::fmt::Write;
+
+use crate::{parse_round, Guide, Reading, Rules, Shape};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Play {
+    pub shape: Shape,
+    pub outcome: Guide,
+    pub shape_score: u32,
+    pub outcome_score: u32,
+}
+
+impl Play {
+    fn new(shape: Shape, opponent: Shape, rules: &Rules) -> Self {
+        Self {
+            shape,
+            outcome: shape.outcome(opponent, rules),
+            shape_score: shape.score(rules),
+            outcome_score: shape.compete(opponent, rules),
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.shape_score + self.outcome_score
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoundBreakdown {
+    pub round: usize,
+    pub opponent: Shape,
+    pub by_shape: Option<Play>,
+    pub by_outcome: Option<Play>,
+}
+
+impl RoundBreakdown {
+    // None when the second column is not a code under that reading, e.g. W
+    // under rules where it names a shape but no outcome.
+    pub fn play(&self, reading: Reading) -> Option<&Play> {
+        match reading {
+            Reading::Shape => self.by_shape.as_ref(),
+            Reading::Outcome => self.by_outcome.as_ref(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub score: u32,
+}
+
+pub fn breakdown(input: &str, rules: &Rules) -> Vec<RoundBreakdown> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let (opponent, code) = parse_round(line, rules);
+            let by_shape = Shape::parse(code, rules).map(|shape| Play::new(shape, opponent, rules));
+            let by_outcome = Guide::parse(code, rules)
+                .map(|guide| Play::new(opponent.choose(guide, rules), opponent, rules));
+            if by_shape.is_none() && by_outcome.is_none() {
+                panic!("line {}: unknown code {code:?}", i + 1);
+            }
+            RoundBreakdown {
+                round: i + 1,
+                opponent,
+                by_shape,
+                by_outcome,
+            }
+        })
+        .collect()
+}
+
+pub fn tally(rounds: &[RoundBreakdown], reading: Reading) -> Tally {
+    let mut tally = Tally::default();
+    for play in rounds.iter().filter_map(|r| r.play(reading)) {
+        match play.outcome {
+            Guide::Win => tally.wins += 1,
+            Guide::Draw => tally.draws += 1,
+            Guide::Lose => tally.losses += 1,
+        }
+        tally.score += play.total();
+    }
+    tally
+}
+
+fn play_columns(play: Option<&Play>, rules: &Rules) -> String {
+    match play {
+        Some(p) => format!(
+            "{:<9} {:<5} {:>5} {:>7} {:>5}",
+            p.shape.name(rules),
+            p.outcome.name(),
+            p.shape_score,
+            p.outcome_score,
+            p.total()
+        ),
+        None => format!("{:<9} {:<5} {:>5} {:>7} {:>5}", "-", "", "-", "-", "-"),
+    }
+}
+
+pub fn breakdown_table(input: &str, rules: &Rules) -> String {
+    let rounds = breakdown(input, rules);
+    let mut s = String::new();
+
+    writeln!(
+        s,
+        "{:>5} {:<9} | {:<9} {:<5} {:>5} {:>7} {:>5} | {:<9} {:<5} {:>5} {:>7} {:>5}",
+        "round",
+        "opponent",
+        "shape",
+        "",
+        "shape",
+        "outcome",
+        "total",
+        "outcome",
+        "",
+        "shape",
+        "outcome",
+        "total"
+    )
+    .unwrap();
+    for r in &rounds {
+        writeln!(
+            s,
+            "{:>5} {:<9} | {} | {}",
+            r.round,
+            r.opponent.name(rules),
+            play_columns(r.by_shape.as_ref(), rules),
+            play_columns(r.by_outcome.as_ref(), rules)
+        )
+        .unwrap();
+    }
+
+    for reading in [Reading::Shape, Reading::Outcome] {
+        let t = tally(&rounds, reading);
+        writeln!(
+            s,
+            "{:?} reading: {} wins, {} draws, {} losses, score {}",
+            reading, t.wins, t.draws, t.losses, t.score
+        )
+        .unwrap();
+    }
+    s
+}
@@ -0,0 +1,213 @@
+// Scores a strategy guide under every possible meaning of its second column.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::{Guide, Rules, Shape};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Reading {
+    // The second column is the shape to play.
+    Shape,
+    // The second column is the outcome the round must end in.
+    Outcome,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Encoding {
+    pub reading: Reading,
+    // (code, meaning) for every code of the second column
+    pub mapping: Vec<(String, String)>,
+    pub total: u32,
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mapping = self
+            .mapping
+            .iter()
+            .map(|(code, meaning)| format!("{code}={meaning}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        write!(f, "{:?} {} -> {}", self.reading, mapping, self.total)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sensitivity {
+    pub reading: Reading,
+    pub code: String,
+    // Mean total over all encodings where the code has this meaning.
+    pub mean_totals: Vec<(String, f64)>,
+    // Largest minus smallest mean total: how much this code's meaning matters.
+    pub spread: f64,
+}
+
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    let mut result = Vec::new();
+    for p in permutations(n - 1) {
+        for i in 0..=p.len() {
+            let mut p = p.clone();
+            p.insert(i, n - 1);
+            result.push(p);
+        }
+    }
+    result.sort();
+    result
+}
+
+// Rounds by opponent shape and code. Every code must mean something in at least
+// one reading.
+fn count_rounds<'a>(
+    input: &'a str,
+    rules: &Rules,
+) -> Result<BTreeMap<(Shape, &'a str), u32>, DecodeError> {
+    let (player_codes, outcome_codes) = (rules.player_codes(), rules.outcome_codes());
+    let mut counts = BTreeMap::new();
+    for (i, line) in input.lines().enumerate() {
+        let error = |message: String| DecodeError {
+            line: i + 1,
+            message,
+        };
+        let (opponent, code) = line
+            .split_once(' ')
+            .ok_or_else(|| error(format!("expected two columns, found {line:?}")))?;
+        let opponent = Shape::parse_opponent(opponent, rules)
+            .ok_or_else(|| error(format!("unknown opponent shape {opponent:?}")))?;
+        if !player_codes.contains(&code) && !outcome_codes.contains(&code) {
+            return Err(error(format!("unknown code {code:?}")));
+        }
+        *counts.entry((opponent, code)).or_insert(0) += 1;
+    }
+    Ok(counts)
+}
+
+// Positions of each counted code among `codes`, or None when some code has no
+// meaning in this reading.
+fn code_indices(counts: &BTreeMap<(Shape, &str), u32>, codes: &[&str]) -> Option<Vec<usize>> {
+    counts
+        .keys()
+        .map(|(_, code)| codes.iter().position(|c| c == code))
+        .collect()
+}
+
+// Scores every encoding of the readings that give all codes in the input a meaning.
+pub fn decode(input: &str, rules: &Rules) -> Result<Vec<Encoding>, DecodeError> {
+    let counts = count_rounds(input, rules)?;
+    let mut encodings = Vec::new();
+
+    let codes = rules.player_codes();
+    if let Some(indices) = code_indices(&counts, &codes) {
+        for p in permutations(codes.len()) {
+            let total = counts
+                .iter()
+                .zip(&indices)
+                .map(|((&(opponent, _), &n), &i)| {
+                    let choose = Shape(p[i]);
+                    n * (choose.score(rules) + choose.compete(opponent, rules))
+                })
+                .sum();
+            let mapping = codes
+                .iter()
+                .zip(p.iter())
+                .map(|(code, &s)| (code.to_string(), Shape(s).name(rules).to_string()))
+                .collect();
+            encodings.push(Encoding {
+                reading: Reading::Shape,
+                mapping,
+                total,
+            });
+        }
+    }
+
+    let codes = rules.outcome_codes();
+    if let Some(indices) = code_indices(&counts, &codes) {
+        for p in permutations(codes.len()) {
+            let total = counts
+                .iter()
+                .zip(&indices)
+                .map(|((&(opponent, _), &n), &i)| {
+                    let choose = opponent.choose(Guide::ALL[p[i]], rules);
+                    n * (choose.score(rules) + choose.compete(opponent, rules))
+                })
+                .sum();
+            let mapping = codes
+                .iter()
+                .zip(p.iter())
+                .map(|(code, &g)| (code.to_string(), Guide::ALL[g].name().to_string()))
+                .collect();
+            encodings.push(Encoding {
+                reading: Reading::Outcome,
+                mapping,
+                total,
+            });
+        }
+    }
+
+    encodings.sort_by(|a, b| {
+        b.total
+            .cmp(&a.total)
+            .then(a.reading.cmp(&b.reading))
+            .then(a.mapping.cmp(&b.mapping))
+    });
+    Ok(encodings)
+}
+
+pub fn matching(input: &str, rules: &Rules, score: u32) -> Result<Vec<Encoding>, DecodeError> {
+    Ok(decode(input, rules)?
+        .into_iter()
+        .filter(|e| e.total == score)
+        .collect())
+}
+
+pub fn sensitivity(encodings: &[Encoding]) -> Vec<Sensitivity> {
+    let mut groups: BTreeMap<(Reading, &str), BTreeMap<&str, Vec<u32>>> = BTreeMap::new();
+    for e in encodings {
+        for (code, meaning) in &e.mapping {
+            groups
+                .entry((e.reading, code))
+                .or_default()
+                .entry(meaning)
+                .or_default()
+                .push(e.total);
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|((reading, code), meanings)| {
+            let mean_totals = meanings
+                .into_iter()
+                .map(|(meaning, totals)| {
+                    let mean = totals.iter().sum::<u32>() as f64 / totals.len() as f64;
+                    (meaning.to_string(), mean)
+                })
+                .collect::<Vec<_>>();
+            let max = mean_totals.iter().map(|m| m.1).fold(f64::MIN, f64::max);
+            let min = mean_totals.iter().map(|m| m.1).fold(f64::MAX, f64::min);
+            Sensitivity {
+                reading,
+                code: code.to_string(),
+                mean_totals,
+                spread: max - min,
+            }
+        })
+        .collect()
+}
@@ -1,94 +1,62 @@
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum Shape {
-    Rock = 1,
-    Paper = 2,
-    Scissors = 3,
-}
-
-impl Shape {
-    fn parse(s: &str) -> Self {
-        if s == "A" || s == "X" {
-            Shape::Rock
-        } else if s == "B" || s == "Y" {
-            Shape::Paper
-        } else {
-            Shape::Scissors
-        }
-    }
+mod breakdown;
+mod decoder;
+mod rules;
+mod synthesis;
+mod tournament;
 
-    fn score(&self) -> u32 {
-        *self as u32
-    }
-
-    fn compete(&self, opponent: Shape) -> u32 {
-        match (*self, opponent) {
-            (Shape::Rock, Shape::Paper) => 0,
-            (Shape::Rock, Shape::Scissors) => 6,
-            (Shape::Paper, Shape::Rock) => 6,
-            (Shape::Paper, Shape::Scissors) => 0,
-            (Shape::Scissors, Shape::Rock) => 0,
-            (Shape::Scissors, Shape::Paper) => 6,
-            (_, _) => 3,
-        }
-    }
+pub use crate::breakdown::{breakdown, breakdown_table, tally, Play, RoundBreakdown, Tally};
+pub use crate::decoder::{
+    decode, matching, sensitivity, DecodeError, Encoding, Reading, Sensitivity,
+};
+pub use crate::rules::{Guide, Rules, RulesError, Shape};
+pub use crate::synthesis::{format_guide, opponent_moves, synthesize, Synthesis};
+pub use crate::tournament::{
+    simulate, strategies, tournament, Constant, FrequencyCounter, GuideStrategy, Markov, Opponent,
+    Random, Standing, Strategy,
+};
 
-    fn choose(&self, guide: Guide) -> Shape {
-        match (*self, guide) {
-            (Shape::Rock, Guide::Lose) => Shape::Scissors,
-            (Shape::Rock, Guide::Win) => Shape::Paper,
-            (Shape::Paper, Guide::Lose) => Shape::Rock,
-            (Shape::Paper, Guide::Win) => Shape::Scissors,
-            (Shape::Scissors, Guide::Lose) => Shape::Paper,
-            (Shape::Scissors, Guide::Win) => Shape::Rock,
-            (shape, _) => shape,
-        }
-    }
-}
-
-#[derive(Clone, Copy)]
-enum Guide {
-    Lose,
-    Draw,
-    Win,
+fn parse_round<'a>(line: &'a str, rules: &Rules) -> (Shape, &'a str) {
+    let (opponent, code) = line.split_once(' ').unwrap();
+    let opponent = Shape::parse_opponent(opponent, rules)
+        .unwrap_or_else(|| panic!("unknown opponent shape {opponent:?}"));
+    (opponent, code)
 }
 
-impl Guide {
-    fn parse(s: &str) -> Self {
-        if s == "X" {
-            Guide::Lose
-        } else if s == "Y" {
-            Guide::Draw
-        } else {
-            Guide::Win
-        }
-    }
-}
-
-pub fn part_one(input: &str) -> u32 {
+// Reads the second column as the shape to play.
+pub fn score_by_shape(input: &str, rules: &Rules) -> u32 {
     input
         .lines()
         .map(|line| {
-            let (opponent, choose) = line.split_once(' ').unwrap();
-            let opponent = Shape::parse(opponent);
-            let choose = Shape::parse(choose);
-            choose.score() + choose.compete(opponent)
+            let (opponent, choose) = parse_round(line, rules);
+            let choose =
+                Shape::parse(choose, rules).unwrap_or_else(|| panic!("unknown shape {choose:?}"));
+            choose.score(rules) + choose.compete(opponent, rules)
         })
         .sum()
 }
 
-pub fn part_two(input: &str) -> u32 {
+// Reads the second column as the outcome the round must end in.
+pub fn score_by_outcome(input: &str, rules: &Rules) -> u32 {
     input
         .lines()
         .map(|line| {
-            let (opponent, guide) = line.split_once(' ').unwrap();
-            let opponent = Shape::parse(opponent);
-            let guide = Guide::parse(guide);
-            let choose = opponent.choose(guide);
-            choose.score() + choose.compete(opponent)
+            let (opponent, guide) = parse_round(line, rules);
+            let guide =
+                Guide::parse(guide, rules).unwrap_or_else(|| panic!("unknown outcome {guide:?}"));
+            let choose = opponent.choose(guide, rules);
+            choose.score(rules) + choose.compete(opponent, rules)
         })
         .sum()
 }
 
+pub fn part_one(input: &str) -> u32 {
+    score_by_shape(input, &Rules::rock_paper_scissors())
+}
+
+pub fn part_two(input: &str) -> u32 {
+    score_by_outcome(input, &Rules::rock_paper_scissors())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,4 +68,215 @@ mod tests {
         assert_eq!(part_one(EXAMPLE), 15);
         assert_eq!(part_two(EXAMPLE), 12);
     }
+
+    #[test]
+    fn lizard_spock_works() {
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+        assert_eq!(rules.len(), 5);
+        assert_eq!(score_by_shape(EXAMPLE, &rules), 15);
+        assert_eq!(score_by_outcome(EXAMPLE, &rules), 4 + 5 + 11);
+
+        let spock = Shape::parse("V", &rules).unwrap();
+        let lizard = Shape::parse("W", &rules).unwrap();
+        assert_eq!(lizard.outcome(spock, &rules), Guide::Win);
+        assert_eq!(score_by_shape("D V\nE W\nE V", &rules), 5 + 4 + 6 + 5 + 3);
+        assert_eq!(spock.choose(Guide::Win, &rules), lizard);
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        let rules = "shape Rock 1 A X\nshape Paper 2 B Y\nbeats Paper Rock\noutcome lose 0 X\noutcome draw 3 Y\noutcome win 6 Z";
+        assert!(Rules::parse(rules)
+            .unwrap_err()
+            .message
+            .contains("odd number"));
+
+        let rules = "shape Rock 1 A X\nbeats Rock Paper";
+        assert_eq!(Rules::parse(rules).unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn decode_works() {
+        let rules = Rules::rock_paper_scissors();
+        let encodings = decode(EXAMPLE, &rules).unwrap();
+        assert_eq!(encodings.len(), 12);
+        assert_eq!(
+            encodings[0].to_string(),
+            "Shape X=Scissors Y=Paper Z=Rock -> 24"
+        );
+        assert_eq!(
+            encodings[11].to_string(),
+            "Shape X=Rock Y=Scissors Z=Paper -> 6"
+        );
+
+        let matches = matching(EXAMPLE, &rules, 12).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].to_string(), "Outcome X=Lose Y=Draw Z=Win -> 12");
+        assert_eq!(matching(EXAMPLE, &rules, 15).unwrap().len(), 8);
+
+        let sensitivity = sensitivity(&encodings);
+        assert_eq!(sensitivity.len(), 6);
+        assert!(sensitivity
+            .iter()
+            .all(|s| s.mean_totals.len() == 3 && s.spread >= 0.0));
+
+        // V and W only name shapes, so the outcome reading is skipped.
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+        let encodings = decode("A V\nE W\n", &rules).unwrap();
+        assert_eq!(encodings.len(), 120);
+        assert!(encodings.iter().all(|e| e.reading == Reading::Shape));
+        assert_eq!(decode("A X\nC Z\n", &rules).unwrap().len(), 126);
+        assert_eq!(
+            decode("A X\nB Q\n", &rules),
+            Err(DecodeError {
+                line: 2,
+                message: "unknown code \"Q\"".to_string()
+            })
+        );
+        assert_eq!(decode("A X\nF Y\n", &rules).unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn tournament_works() {
+        let rules = Rules::rock_paper_scissors();
+        let opponents = [
+            Opponent::Recorded,
+            Opponent::Cycle,
+            Opponent::Biased {
+                seed: 7,
+                weights: vec![8, 1, 1],
+            },
+        ];
+        let standings = tournament(EXAMPLE, &rules, &opponents);
+        assert_eq!(standings.len(), 18);
+
+        let guide = &standings[0];
+        assert_eq!(
+            (guide.strategy.as_str(), guide.opponent.as_str()),
+            ("guide", "recorded")
+        );
+        assert_eq!(
+            (guide.score, guide.wins, guide.draws, guide.losses),
+            (12, 1, 1, 1)
+        );
+        assert_eq!(standings[1].strategy, "always rock");
+        assert_eq!(standings[1].versus_guide, standings[1].score as i64 - 12);
+
+        // Against a repeating cycle, a markov predictor learns the next shape.
+        let cycle = (0..300).map(|i| Shape(i % 3)).collect::<Vec<_>>();
+        let standing = simulate(&mut Markov::new(1), &cycle, &rules);
+        assert!(standing.win_rate() > 0.95);
+
+        let biased = Opponent::Biased {
+            seed: 7,
+            weights: vec![8, 1, 1],
+        }
+        .moves(&"A X\n".repeat(300), &rules);
+        let standing = simulate(&mut FrequencyCounter::default(), &biased, &rules);
+        assert!(standing.win_rate() > 0.6);
+    }
+
+    #[test]
+    fn synthesis_works() {
+        let rules = Rules::rock_paper_scissors();
+        let synthesis = synthesize(EXAMPLE, &rules, 12);
+        assert_eq!((synthesis.min, synthesis.max, synthesis.count), (6, 24, 1));
+        let guide = synthesis.guide.unwrap();
+        assert_eq!(guide, vec![Guide::Draw, Guide::Lose, Guide::Win]);
+        assert_eq!(
+            format_guide(EXAMPLE, &guide, &rules),
+            EXAMPLE.to_string() + "\n"
+        );
+
+        let synthesis = synthesize(EXAMPLE, &rules, 13);
+        assert_eq!(synthesis.guide, None);
+        assert_eq!(synthesis.nearest, vec![12, 14]);
+
+        let synthesis = synthesize(EXAMPLE, &rules, 30);
+        assert_eq!(synthesis.nearest, vec![24]);
+
+        let synthesis = synthesize(EXAMPLE, &rules, 15);
+        let guide = synthesis.guide.unwrap();
+        assert_eq!(
+            score_by_outcome(&format_guide(EXAMPLE, &guide, &rules), &rules),
+            15
+        );
+    }
+
+    #[test]
+    fn breakdown_works() {
+        let rules = Rules::rock_paper_scissors();
+        let rounds = breakdown(EXAMPLE, &rules);
+        assert_eq!(rounds.len(), 3);
+        assert_eq!(
+            rounds[0].by_shape,
+            Some(Play {
+                shape: Shape(1),
+                outcome: Guide::Win,
+                shape_score: 2,
+                outcome_score: 6
+            })
+        );
+        assert_eq!(
+            rounds[0].by_outcome,
+            Some(Play {
+                shape: Shape(0),
+                outcome: Guide::Draw,
+                shape_score: 1,
+                outcome_score: 3
+            })
+        );
+
+        assert_eq!(
+            tally(&rounds, Reading::Shape),
+            Tally {
+                wins: 1,
+                draws: 1,
+                losses: 1,
+                score: 15
+            }
+        );
+        assert_eq!(
+            tally(&rounds, Reading::Outcome),
+            Tally {
+                wins: 1,
+                draws: 1,
+                losses: 1,
+                score: 12
+            }
+        );
+
+        let table = breakdown_table(EXAMPLE, &rules);
+        assert_eq!(table.lines().count(), 6);
+        assert!(table.contains("Outcome reading: 1 wins, 1 draws, 1 losses, score 12"));
+
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+        let rounds = breakdown("A W\nE V\nC Z\n", &rules);
+        assert_eq!(rounds[0].by_outcome, None);
+        assert_eq!(rounds[1].by_outcome, None);
+        assert!(rounds[2].by_outcome.is_some());
+        assert_eq!(
+            tally(&rounds, Reading::Shape),
+            Tally {
+                wins: 0,
+                draws: 2,
+                losses: 1,
+                score: 3 + 5 + 4 + 3 + 3
+            }
+        );
+        assert_eq!(
+            tally(&rounds, Reading::Outcome),
+            Tally {
+                wins: 1,
+                draws: 0,
+                losses: 0,
+                score: 5 + 6
+            }
+        );
+        assert!(breakdown_table("A W\n", &rules)
+            .lines()
+            .nth(1)
+            .unwrap()
+            .ends_with("-"));
+    }
 }
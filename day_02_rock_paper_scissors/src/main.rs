@@ -1,8 +1,130 @@
-use day_02_rock_paper_scissors::{part_one, part_two};
+use day_02_rock_paper_scissors::{
+    breakdown_table, decode, format_guide, score_by_outcome, score_by_shape, sensitivity,
+    synthesize, tournament, Opponent, Rules,
+};
 
 const INPUT: &str = include_str!("../data/input.txt");
 
+// Takes `--rules PATH` out of the arguments, defaulting to rock paper scissors.
+fn rules(args: &mut Vec<String>) -> Rules {
+    match args.iter().position(|a| a == "--rules") {
+        Some(i) => {
+            let Some(path) = args.get(i + 1) else {
+                eprintln!("--rules needs a path");
+                std::process::exit(1);
+            };
+            let rules = Rules::load(path).unwrap_or_else(|e| {
+                eprintln!("{e}");
+                std::process::exit(1);
+            });
+            args.drain(i..i + 2);
+            rules
+        }
+        None => Rules::rock_paper_scissors(),
+    }
+}
+
 fn main() {
-    println!("part one answer: {}", part_one(INPUT));
-    println!("part two answer: {}", part_two(INPUT));
-}
\ No newline at end of file
+    let mut args = std::env::args().collect::<Vec<_>>();
+    let rules = rules(&mut args);
+    if args.get(1).map(String::as_str) == Some("decode") {
+        let known = match args.get(2).map(|s| s.parse::<u32>()) {
+            None => None,
+            Some(Ok(known)) => Some(known),
+            Some(Err(_)) => {
+                eprintln!("usage: decode [KNOWN_SCORE] [--rules PATH]");
+                std::process::exit(1);
+            }
+        };
+        let encodings = decode(INPUT, &rules).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1);
+        });
+        for e in &encodings {
+            let mark = if Some(e.total) == known { " *" } else { "" };
+            println!("{e}{mark}");
+        }
+        for s in sensitivity(&encodings) {
+            let means = s
+                .mean_totals
+                .iter()
+                .map(|(meaning, mean)| format!("{meaning}={mean:.1}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            println!(
+                "{:?} {}: {} (spread {:.1})",
+                s.reading, s.code, means, s.spread
+            );
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("tournament") {
+        let opponents = [
+            Opponent::Recorded,
+            Opponent::Cycle,
+            Opponent::Uniform { seed: 1 },
+            Opponent::Biased {
+                seed: 1,
+                weights: vec![5, 3, 2],
+            },
+        ];
+        println!(
+            "{:<16} {:<16} {:>6} {:>6} {:>6} {:>6} {:>8}",
+            "strategy", "opponent", "score", "win", "draw", "loss", "vs guide"
+        );
+        for s in tournament(INPUT, &rules, &opponents) {
+            println!(
+                "{:<16} {:<16} {:>6} {:>6.3} {:>6.3} {:>6.3} {:>+8}",
+                s.strategy,
+                s.opponent,
+                s.score,
+                s.win_rate(),
+                s.draw_rate(),
+                s.loss_rate(),
+                s.versus_guide
+            );
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("breakdown") {
+        print!("{}", breakdown_table(INPUT, &rules));
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("synthesize") {
+        let Some(target) = args.get(2).and_then(|s| s.parse::<u32>().ok()) else {
+            eprintln!("usage: synthesize TARGET [--rules PATH]");
+            std::process::exit(1);
+        };
+        let synthesis = synthesize(INPUT, &rules, target);
+        match &synthesis.guide {
+            Some(guide) => print!("{}", format_guide(INPUT, guide, &rules)),
+            None => println!(
+                "{target} is not reachable, nearest: {:?}",
+                synthesis.nearest
+            ),
+        }
+        let at_least = if synthesis.count == u128::MAX {
+            "at least "
+        } else {
+            ""
+        };
+        eprintln!(
+            "min {}, max {}, {at_least}{} guides score {target}",
+            synthesis.min, synthesis.max, synthesis.count
+        );
+        return;
+    }
+
+    if let Some(arg) = args.get(1) {
+        eprintln!(
+            "unknown subcommand {arg:?}, expected decode, tournament, breakdown or synthesize"
+        );
+        std::process::exit(1);
+    }
+
+    println!("part one answer: {}", score_by_shape(INPUT, &rules));
+    println!("part two answer: {}", score_by_outcome(INPUT, &rules));
+}
@@ -0,0 +1,316 @@
+use std::fmt;
+
+const ROCK_PAPER_SCISSORS: &str = include_str!("../data/rock_paper_scissors.txt");
+const ROCK_PAPER_SCISSORS_LIZARD_SPOCK: &str =
+    include_str!("../data/rock_paper_scissors_lizard_spock.txt");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Shape(pub usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Guide {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl Guide {
+    pub const ALL: [Guide; 3] = [Guide::Lose, Guide::Draw, Guide::Win];
+
+    pub fn parse(s: &str, rules: &Rules) -> Option<Self> {
+        Guide::ALL
+            .into_iter()
+            .find(|g| rules.outcome_codes[g.index()] == s)
+    }
+
+    pub fn code<'a>(&self, rules: &'a Rules) -> &'a str {
+        &rules.outcome_codes[self.index()]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Guide::Lose => "Lose",
+            Guide::Draw => "Draw",
+            Guide::Win => "Win",
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RulesError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ShapeRule {
+    name: String,
+    score: u32,
+    opponent_code: String,
+    player_code: String,
+}
+
+// A hand game: its shapes, which shape beats which, and what each shape and
+// outcome is worth.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    shapes: Vec<ShapeRule>,
+    // beats[a][b] is true when shape a beats shape b
+    beats: Vec<Vec<bool>>,
+    outcome_scores: [u32; 3],
+    outcome_codes: [String; 3],
+}
+
+impl Rules {
+    pub fn rock_paper_scissors() -> Self {
+        Rules::parse(ROCK_PAPER_SCISSORS).unwrap()
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Rules::parse(ROCK_PAPER_SCISSORS_LIZARD_SPOCK).unwrap()
+    }
+
+    pub fn load(path: &str) -> Result<Self, RulesError> {
+        let s = std::fs::read_to_string(path).map_err(|e| RulesError {
+            line: 0,
+            message: format!("{path}: {e}"),
+        })?;
+        Rules::parse(&s)
+    }
+
+    pub fn parse(s: &str) -> Result<Self, RulesError> {
+        let mut shapes: Vec<ShapeRule> = Vec::new();
+        let mut beats = Vec::new();
+        let mut outcomes: [Option<(u32, String)>; 3] = Default::default();
+
+        for (i, line) in s.lines().enumerate() {
+            let error = |message: String| RulesError {
+                line: i + 1,
+                message,
+            };
+            let tokens = line.split_whitespace().collect::<Vec<_>>();
+            match tokens[..] {
+                [] => {}
+                [comment, ..] if comment.starts_with('#') => {}
+                ["shape", name, score, opponent_code, player_code] => {
+                    if shapes.iter().any(|s| s.name == name) {
+                        return Err(error(format!("shape {name} is defined twice")));
+                    }
+                    let score = score
+                        .parse()
+                        .map_err(|_| error(format!("invalid score {score}")))?;
+                    shapes.push(ShapeRule {
+                        name: name.to_string(),
+                        score,
+                        opponent_code: opponent_code.to_string(),
+                        player_code: player_code.to_string(),
+                    });
+                }
+                ["beats", winner, loser] => {
+                    let index = |name: &str| {
+                        shapes
+                            .iter()
+                            .position(|s| s.name == name)
+                            .ok_or_else(|| error(format!("unknown shape {name}")))
+                    };
+                    beats.push((index(winner)?, index(loser)?, i + 1));
+                }
+                ["outcome", outcome, score, code] => {
+                    let guide = match outcome {
+                        "lose" => Guide::Lose,
+                        "draw" => Guide::Draw,
+                        "win" => Guide::Win,
+                        _ => return Err(error(format!("unknown outcome {outcome}"))),
+                    };
+                    let score = score
+                        .parse()
+                        .map_err(|_| error(format!("invalid score {score}")))?;
+                    outcomes[guide.index()] = Some((score, code.to_string()));
+                }
+                _ => return Err(error(format!("unrecognised rule {line:?}"))),
+            }
+        }
+
+        let n = shapes.len();
+        let mut table = vec![vec![false; n]; n];
+        for (winner, loser, line) in beats {
+            if winner == loser || table[loser][winner] {
+                return Err(RulesError {
+                    line,
+                    message: format!(
+                        "{} and {} cannot beat each other",
+                        shapes[winner].name, shapes[loser].name
+                    ),
+                });
+            }
+            table[winner][loser] = true;
+        }
+
+        let [lose, draw, win] = outcomes;
+        let missing = |name: &str| RulesError {
+            line: 0,
+            message: format!("missing {name} outcome"),
+        };
+        let (lose, draw, win) = (
+            lose.ok_or_else(|| missing("lose"))?,
+            draw.ok_or_else(|| missing("draw"))?,
+            win.ok_or_else(|| missing("win"))?,
+        );
+
+        let rules = Rules {
+            shapes,
+            beats: table,
+            outcome_scores: [lose.0, draw.0, win.0],
+            outcome_codes: [lose.1, draw.1, win.1],
+        };
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    // Every pair of shapes must have a winner, every shape must beat as many shapes
+    // as it loses to, and codes must not be ambiguous.
+    fn validate(&self) -> Result<(), RulesError> {
+        let error = |message: String| Err(RulesError { line: 0, message });
+        let n = self.shapes.len();
+        if n.is_multiple_of(2) {
+            return error(format!(
+                "a balanced game needs an odd number of shapes, found {n}"
+            ));
+        }
+
+        for a in 0..n {
+            for b in a + 1..n {
+                if !self.beats[a][b] && !self.beats[b][a] {
+                    return error(format!(
+                        "no rule between {} and {}",
+                        self.shapes[a].name, self.shapes[b].name
+                    ));
+                }
+            }
+            let wins = self.beats[a].iter().filter(|&&w| w).count();
+            if wins != (n - 1) / 2 {
+                return error(format!(
+                    "{} beats {} shapes, expected {}",
+                    self.shapes[a].name,
+                    wins,
+                    (n - 1) / 2
+                ));
+            }
+        }
+
+        for a in 0..n {
+            for b in a + 1..n {
+                if self.shapes[a].opponent_code == self.shapes[b].opponent_code
+                    || self.shapes[a].player_code == self.shapes[b].player_code
+                {
+                    return error(format!(
+                        "{} and {} share a code",
+                        self.shapes[a].name, self.shapes[b].name
+                    ));
+                }
+            }
+        }
+        if self.outcome_codes[0] == self.outcome_codes[1]
+            || self.outcome_codes[1] == self.outcome_codes[2]
+            || self.outcome_codes[0] == self.outcome_codes[2]
+        {
+            return error("outcomes share a code".to_string());
+        }
+
+        Ok(())
+    }
+
+    pub fn shapes(&self) -> impl Iterator<Item = Shape> {
+        (0..self.shapes.len()).map(Shape)
+    }
+
+    pub fn len(&self) -> usize {
+        self.shapes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty()
+    }
+
+    pub fn outcome_score(&self, guide: Guide) -> u32 {
+        self.outcome_scores[guide.index()]
+    }
+
+    pub fn player_codes(&self) -> Vec<&str> {
+        self.shapes.iter().map(|s| s.player_code.as_str()).collect()
+    }
+
+    pub fn outcome_codes(&self) -> Vec<&str> {
+        self.outcome_codes.iter().map(String::as_str).collect()
+    }
+}
+
+impl Shape {
+    pub fn parse_opponent(s: &str, rules: &Rules) -> Option<Self> {
+        rules
+            .shapes
+            .iter()
+            .position(|r| r.opponent_code == s)
+            .map(Shape)
+    }
+
+    pub fn parse(s: &str, rules: &Rules) -> Option<Self> {
+        rules
+            .shapes
+            .iter()
+            .position(|r| r.player_code == s)
+            .map(Shape)
+    }
+
+    pub fn name<'a>(&self, rules: &'a Rules) -> &'a str {
+        &rules.shapes[self.0].name
+    }
+
+    pub fn opponent_code<'a>(&self, rules: &'a Rules) -> &'a str {
+        &rules.shapes[self.0].opponent_code
+    }
+
+    pub fn score(&self, rules: &Rules) -> u32 {
+        rules.shapes[self.0].score
+    }
+
+    pub fn outcome(&self, opponent: Shape, rules: &Rules) -> Guide {
+        if rules.beats[self.0][opponent.0] {
+            Guide::Win
+        } else if rules.beats[opponent.0][self.0] {
+            Guide::Lose
+        } else {
+            Guide::Draw
+        }
+    }
+
+    pub fn compete(&self, opponent: Shape, rules: &Rules) -> u32 {
+        rules.outcome_score(self.outcome(opponent, rules))
+    }
+
+    // The shape to play against self to get the guided outcome. When several shapes
+    // would do, the highest scoring one is played.
+    pub fn choose(&self, guide: Guide, rules: &Rules) -> Shape {
+        rules
+            .shapes()
+            .filter(|s| s.outcome(*self, rules) == guide)
+            .max_by_key(|s| (s.score(rules), std::cmp::Reverse(s.0)))
+            .unwrap()
+    }
+}
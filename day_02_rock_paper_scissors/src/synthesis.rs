@@ -0,0 +1,122 @@
+// Builds a Lose/Draw/Win guide that reaches an exact total score.
+
+use crate::{Guide, Rules, Shape};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Synthesis {
+    pub target: u32,
+    pub min: u32,
+    pub max: u32,
+    // Number of guides that score exactly the target, saturating at u128::MAX.
+    pub count: u128,
+    pub guide: Option<Vec<Guide>>,
+    // The closest achievable totals below and above the target when it is not reachable.
+    pub nearest: Vec<u32>,
+}
+
+// Reachable totals as bits, one per total.
+struct Reachable(Vec<u64>);
+
+impl Reachable {
+    fn new(counts: &[u128]) -> Self {
+        let mut bits = vec![0u64; counts.len().div_ceil(64)];
+        for (s, _) in counts.iter().enumerate().filter(|(_, &c)| c > 0) {
+            bits[s / 64] |= 1 << (s % 64);
+        }
+        Self(bits)
+    }
+
+    fn contains(&self, s: usize) -> bool {
+        self.0.get(s / 64).is_some_and(|b| b & (1 << (s % 64)) != 0)
+    }
+}
+
+fn round_score(opponent: Shape, guide: Guide, rules: &Rules) -> u32 {
+    let choose = opponent.choose(guide, rules);
+    choose.score(rules) + choose.compete(opponent, rules)
+}
+
+pub fn opponent_moves(input: &str, rules: &Rules) -> Vec<Shape> {
+    input
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|code| {
+            Shape::parse_opponent(code, rules)
+                .unwrap_or_else(|| panic!("unknown opponent shape {code:?}"))
+        })
+        .collect()
+}
+
+pub fn synthesize(input: &str, rules: &Rules, target: u32) -> Synthesis {
+    let opponents = opponent_moves(input, rules);
+    let scores = opponents
+        .iter()
+        .map(|&o| Guide::ALL.map(|g| round_score(o, g, rules)))
+        .collect::<Vec<_>>();
+    let max = scores.iter().map(|s| *s.iter().max().unwrap()).sum::<u32>();
+    let min = scores.iter().map(|s| *s.iter().min().unwrap()).sum::<u32>();
+
+    // layers[i] holds the totals the first i rounds can reach.
+    let mut layers = Vec::with_capacity(scores.len() + 1);
+    let mut counts = vec![0u128; max as usize + 1];
+    counts[0] = 1;
+    layers.push(Reachable::new(&counts[..1]));
+    let mut reach = 0;
+    for round in &scores {
+        let mut next = vec![0u128; max as usize + 1];
+        for (s, &c) in counts.iter().enumerate().filter(|(_, &c)| c > 0) {
+            for &points in round {
+                let t = s + points as usize;
+                next[t] = next[t].saturating_add(c);
+            }
+        }
+        counts = next;
+        reach += *round.iter().max().unwrap() as usize;
+        layers.push(Reachable::new(&counts[..=reach]));
+    }
+
+    let reachable = |s: u32| (s as usize) < counts.len() && counts[s as usize] > 0;
+    if !reachable(target) {
+        let below = (min..target.min(max + 1)).rev().find(|&s| reachable(s));
+        let above = (target + 1..=max).find(|&s| reachable(s));
+        return Synthesis {
+            target,
+            min,
+            max,
+            count: 0,
+            guide: None,
+            nearest: below.into_iter().chain(above).collect(),
+        };
+    }
+
+    let mut guide = vec![Guide::Draw; scores.len()];
+    let mut s = target as usize;
+    for i in (0..scores.len()).rev() {
+        let g = Guide::ALL
+            .into_iter()
+            .zip(scores[i])
+            .find(|&(_, points)| s >= points as usize && layers[i].contains(s - points as usize))
+            .map(|(g, _)| g)
+            .unwrap();
+        guide[i] = g;
+        s -= round_score(opponents[i], g, rules) as usize;
+    }
+
+    Synthesis {
+        target,
+        min,
+        max,
+        count: counts[target as usize],
+        guide: Some(guide),
+        nearest: vec![],
+    }
+}
+
+// Writes the guide in the puzzle's own `A Y` format.
+pub fn format_guide(input: &str, guide: &[Guide], rules: &Rules) -> String {
+    opponent_moves(input, rules)
+        .iter()
+        .zip(guide)
+        .map(|(o, g)| format!("{} {}\n", o.opponent_code(rules), g.code(rules)))
+        .collect()
+}
@@ -0,0 +1,333 @@
+// Plays strategies round by round against recorded and synthetic opponents.
+
+use std::collections::HashMap;
+
+use crate::{parse_round, Guide, Rules, Shape};
+
+pub trait Strategy {
+    fn name(&self) -> String;
+
+    fn play(&mut self, round: usize, rules: &Rules) -> Shape;
+
+    // Called after every round with the shape the opponent played.
+    fn observe(&mut self, _opponent: Shape, _rules: &Rules) {}
+}
+
+// Replays the moves the strategy guide asks for, reading the second column as the
+// outcome to reach against the recorded opponent.
+pub struct GuideStrategy {
+    moves: Vec<Shape>,
+}
+
+impl GuideStrategy {
+    pub fn new(input: &str, rules: &Rules) -> Self {
+        let moves = input
+            .lines()
+            .map(|line| {
+                let (opponent, guide) = parse_round(line, rules);
+                let guide = Guide::parse(guide, rules)
+                    .unwrap_or_else(|| panic!("unknown outcome {guide:?}"));
+                opponent.choose(guide, rules)
+            })
+            .collect();
+        Self { moves }
+    }
+}
+
+impl Strategy for GuideStrategy {
+    fn name(&self) -> String {
+        "guide".to_string()
+    }
+
+    fn play(&mut self, round: usize, _rules: &Rules) -> Shape {
+        self.moves[round % self.moves.len()]
+    }
+}
+
+pub struct Constant {
+    shape: Shape,
+    name: String,
+}
+
+impl Constant {
+    pub fn new(shape: Shape, rules: &Rules) -> Self {
+        Self {
+            shape,
+            name: format!("always {}", shape.name(rules).to_lowercase()),
+        }
+    }
+}
+
+impl Strategy for Constant {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn play(&mut self, _round: usize, _rules: &Rules) -> Shape {
+        self.shape
+    }
+}
+
+pub struct Random(XorShift);
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self(XorShift::new(seed))
+    }
+}
+
+impl Strategy for Random {
+    fn name(&self) -> String {
+        "random".to_string()
+    }
+
+    fn play(&mut self, _round: usize, rules: &Rules) -> Shape {
+        Shape(self.0.below(rules.len() as u64) as usize)
+    }
+}
+
+// Beats whatever the opponent has played most so far.
+#[derive(Default)]
+pub struct FrequencyCounter {
+    counts: Vec<u32>,
+}
+
+impl Strategy for FrequencyCounter {
+    fn name(&self) -> String {
+        "frequency".to_string()
+    }
+
+    fn play(&mut self, _round: usize, rules: &Rules) -> Shape {
+        most_likely(&self.counts).choose(Guide::Win, rules)
+    }
+
+    fn observe(&mut self, opponent: Shape, rules: &Rules) {
+        self.counts.resize(rules.len(), 0);
+        self.counts[opponent.0] += 1;
+    }
+}
+
+// Predicts the opponent's next shape from its last `order` shapes, learning the
+// transitions as the game goes on, and falls back to overall frequency.
+pub struct Markov {
+    order: usize,
+    history: Vec<Shape>,
+    transitions: HashMap<Vec<Shape>, Vec<u32>>,
+    frequency: FrequencyCounter,
+}
+
+impl Markov {
+    pub fn new(order: usize) -> Self {
+        Self {
+            order,
+            history: vec![],
+            transitions: HashMap::new(),
+            frequency: FrequencyCounter::default(),
+        }
+    }
+
+    fn context(&self) -> Option<&[Shape]> {
+        let n = self.history.len();
+        (n >= self.order).then(|| &self.history[n - self.order..])
+    }
+}
+
+impl Strategy for Markov {
+    fn name(&self) -> String {
+        format!("markov order {}", self.order)
+    }
+
+    fn play(&mut self, round: usize, rules: &Rules) -> Shape {
+        match self.context().and_then(|c| self.transitions.get(c)) {
+            Some(counts) => most_likely(counts).choose(Guide::Win, rules),
+            None => self.frequency.play(round, rules),
+        }
+    }
+
+    fn observe(&mut self, opponent: Shape, rules: &Rules) {
+        if let Some(context) = self.context() {
+            let counts = self.transitions.entry(context.to_vec()).or_default();
+            counts.resize(rules.len(), 0);
+            counts[opponent.0] += 1;
+        }
+        self.history.push(opponent);
+        self.frequency.observe(opponent, rules);
+    }
+}
+
+fn most_likely(counts: &[u32]) -> Shape {
+    let i = (0..counts.len())
+        .max_by_key(|&i| (counts[i], std::cmp::Reverse(i)))
+        .unwrap_or(0);
+    Shape(i)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Opponent {
+    // The first column of the strategy guide.
+    Recorded,
+    // Plays every shape in turn.
+    Cycle,
+    Uniform { seed: u64 },
+    // Picks shapes at random in proportion to the weights.
+    Biased { seed: u64, weights: Vec<u32> },
+}
+
+impl Opponent {
+    pub fn name(&self) -> String {
+        match self {
+            Opponent::Recorded => "recorded".to_string(),
+            Opponent::Cycle => "cycle".to_string(),
+            Opponent::Uniform { .. } => "uniform".to_string(),
+            Opponent::Biased { weights, .. } => format!("biased {weights:?}"),
+        }
+    }
+
+    pub fn moves(&self, input: &str, rules: &Rules) -> Vec<Shape> {
+        let rounds = input.lines().count();
+        match self {
+            Opponent::Recorded => input
+                .lines()
+                .map(|line| parse_round(line, rules).0)
+                .collect(),
+            Opponent::Cycle => (0..rounds).map(|i| Shape(i % rules.len())).collect(),
+            Opponent::Uniform { seed } => {
+                let mut rng = XorShift::new(*seed);
+                (0..rounds)
+                    .map(|_| Shape(rng.below(rules.len() as u64) as usize))
+                    .collect()
+            }
+            Opponent::Biased { seed, weights } => {
+                let mut rng = XorShift::new(*seed);
+                let total = weights
+                    .iter()
+                    .take(rules.len())
+                    .map(|&w| w as u64)
+                    .sum::<u64>()
+                    .max(1);
+                (0..rounds)
+                    .map(|_| {
+                        let mut r = rng.below(total);
+                        let i = weights
+                            .iter()
+                            .take(rules.len())
+                            .position(|&w| {
+                                let hit = r < w as u64;
+                                r = r.saturating_sub(w as u64);
+                                hit
+                            })
+                            .unwrap_or(0);
+                        Shape(i)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standing {
+    pub strategy: String,
+    pub opponent: String,
+    pub score: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    // Score minus the guide's score against the same opponent.
+    pub versus_guide: i64,
+}
+
+impl Standing {
+    pub fn rounds(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.rounds().max(1) as f64
+    }
+
+    pub fn draw_rate(&self) -> f64 {
+        self.draws as f64 / self.rounds().max(1) as f64
+    }
+
+    pub fn loss_rate(&self) -> f64 {
+        self.losses as f64 / self.rounds().max(1) as f64
+    }
+}
+
+pub fn simulate(strategy: &mut dyn Strategy, opponent: &[Shape], rules: &Rules) -> Standing {
+    let mut standing = Standing {
+        strategy: strategy.name(),
+        opponent: String::new(),
+        score: 0,
+        wins: 0,
+        draws: 0,
+        losses: 0,
+        versus_guide: 0,
+    };
+
+    for (round, &theirs) in opponent.iter().enumerate() {
+        let ours = strategy.play(round, rules);
+        standing.score += ours.score(rules) + ours.compete(theirs, rules);
+        match ours.outcome(theirs, rules) {
+            Guide::Win => standing.wins += 1,
+            Guide::Draw => standing.draws += 1,
+            Guide::Lose => standing.losses += 1,
+        }
+        strategy.observe(theirs, rules);
+    }
+
+    standing
+}
+
+pub fn strategies(input: &str, rules: &Rules) -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(GuideStrategy::new(input, rules)),
+        Box::new(Constant::new(Shape(0), rules)),
+        Box::new(Random::new(2022)),
+        Box::new(FrequencyCounter::default()),
+        Box::new(Markov::new(1)),
+        Box::new(Markov::new(2)),
+    ]
+}
+
+// Runs every strategy from `strategies` against every opponent. The first strategy
+// is taken to be the guide that the others are compared with.
+pub fn tournament(input: &str, rules: &Rules, opponents: &[Opponent]) -> Vec<Standing> {
+    let mut standings = Vec::new();
+
+    for opponent in opponents {
+        let moves = opponent.moves(input, rules);
+        let results = strategies(input, rules)
+            .iter_mut()
+            .map(|s| simulate(s.as_mut(), &moves, rules))
+            .collect::<Vec<_>>();
+        let guide = results.first().map(|r| r.score as i64).unwrap_or(0);
+        for mut r in results {
+            r.opponent = opponent.name();
+            r.versus_guide = r.score as i64 - guide;
+            standings.push(r);
+        }
+    }
+
+    standings
+}
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}
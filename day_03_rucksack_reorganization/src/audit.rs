@@ -0,0 +1,241 @@
+// Lists every item type found in both compartments and plans the fewest swaps
+// between compartments that leave exactly one shared type.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{char_to_priority, PriorityScheme, RucksackError};
+
+const MAX_SWAPS: usize = 8;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SharedItem {
+    pub item: char,
+    pub priority: u32,
+    // How many times the item appears in the first and second compartment.
+    pub first: usize,
+    pub second: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Audit {
+    pub line: usize,
+    pub shared: Vec<SharedItem>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Swap {
+    // Positions in the rucksack line, one in each compartment.
+    pub first: usize,
+    pub second: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RepackOutcome {
+    Repacked { swaps: Vec<Swap>, corrected: String },
+    // No arrangement of the items leaves exactly one shared type.
+    Impossible,
+    // An arrangement exists but needs more than MAX_SWAPS swaps.
+    TooManySwaps,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Repack {
+    pub line: usize,
+    pub outcome: RepackOutcome,
+}
+
+type Counts = BTreeMap<char, (usize, usize)>;
+
+fn compartments(
+    line: &str,
+    n: usize,
+    scheme: &dyn PriorityScheme,
+) -> Result<Vec<char>, RucksackError> {
+    let items = line.chars().collect::<Vec<_>>();
+    if items.len() % 2 != 0 {
+        return Err(RucksackError::OddLength { line: n });
+    }
+    for &c in &items {
+        char_to_priority(c, scheme).map_err(|item| RucksackError::InvalidItem { line: n, item })?;
+    }
+    Ok(items)
+}
+
+fn counts(items: &[char]) -> Counts {
+    let half = items.len() / 2;
+    let mut counts = Counts::new();
+    for (i, &c) in items.iter().enumerate() {
+        let entry = counts.entry(c).or_default();
+        if i < half {
+            entry.0 += 1;
+        } else {
+            entry.1 += 1;
+        }
+    }
+    counts
+}
+
+fn shared(counts: &Counts) -> impl Iterator<Item = (char, usize, usize)> + '_ {
+    counts
+        .iter()
+        .filter(|(_, &(a, b))| a > 0 && b > 0)
+        .map(|(&c, &(a, b))| (c, a, b))
+}
+
+pub fn audit(input: &str, scheme: &dyn PriorityScheme) -> Result<Vec<Audit>, RucksackError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let items = compartments(line, i + 1, scheme)?;
+            let shared = shared(&counts(&items))
+                .map(|(item, first, second)| SharedItem {
+                    item,
+                    priority: char_to_priority(item, scheme).unwrap(),
+                    first,
+                    second,
+                })
+                .collect();
+            Ok(Audit {
+                line: i + 1,
+                shared,
+            })
+        })
+        .collect()
+}
+
+// Each swap moves one item out of each compartment, so it can take at most two
+// copies away from the types that must stop being shared.
+fn lower_bound(counts: &Counts) -> usize {
+    let mins = shared(counts).map(|(_, a, b)| a.min(b)).collect::<Vec<_>>();
+    match mins.iter().max() {
+        Some(max) => (mins.iter().sum::<usize>() - max).div_ceil(2),
+        None => 1,
+    }
+}
+
+// Iterative deepening search over compartment counts. `failed` remembers the
+// deepest search that already failed from a state.
+fn search(
+    counts: &mut Counts,
+    depth: usize,
+    plan: &mut Vec<(char, char)>,
+    failed: &mut HashMap<Counts, usize>,
+) -> bool {
+    let shared_count = shared(counts).count();
+    if shared_count == 1 {
+        return true;
+    }
+    if lower_bound(counts) > depth {
+        return false;
+    }
+    if failed.get(counts).is_some_and(|&d| d >= depth) {
+        return false;
+    }
+
+    // A swap that touches no shared type cannot help while some type is shared, and
+    // a new shared type needs one with at least two copies.
+    let useful = |c: char| {
+        let (a, b) = counts[&c];
+        if shared_count > 0 {
+            a > 0 && b > 0
+        } else {
+            a + b >= 2
+        }
+    };
+    let firsts = counts.iter().filter(|(_, c)| c.0 > 0).map(|(&x, _)| x);
+    let seconds = counts.iter().filter(|(_, c)| c.1 > 0).map(|(&y, _)| y);
+    let swaps = firsts
+        .flat_map(|x| seconds.clone().map(move |y| (x, y)))
+        .filter(|&(x, y)| x != y && (useful(x) || useful(y)))
+        .collect::<Vec<_>>();
+
+    for (x, y) in swaps {
+        counts.get_mut(&x).unwrap().0 -= 1;
+        counts.get_mut(&x).unwrap().1 += 1;
+        counts.get_mut(&y).unwrap().1 -= 1;
+        counts.get_mut(&y).unwrap().0 += 1;
+        plan.push((x, y));
+
+        if search(counts, depth - 1, plan, failed) {
+            return true;
+        }
+
+        plan.pop();
+        counts.get_mut(&y).unwrap().0 -= 1;
+        counts.get_mut(&y).unwrap().1 += 1;
+        counts.get_mut(&x).unwrap().1 -= 1;
+        counts.get_mut(&x).unwrap().0 += 1;
+    }
+
+    failed.insert(counts.clone(), depth);
+    false
+}
+
+// Some type must be split across the compartments while every other type sits
+// wholly in one of them, so the rest of the first compartment is a subset sum
+// of the other types' totals.
+fn repackable(counts: &Counts, half: usize) -> bool {
+    let totals = counts.values().map(|&(a, b)| a + b).collect::<Vec<_>>();
+    (0..totals.len()).filter(|&s| totals[s] >= 2).any(|s| {
+        let mut sums = vec![false; half + 1];
+        sums[0] = true;
+        for (_, &t) in totals.iter().enumerate().filter(|&(i, _)| i != s) {
+            for n in (t..=half).rev() {
+                sums[n] |= sums[n - t];
+            }
+        }
+        (1..totals[s].min(half + 1)).any(|x| sums[half - x])
+    })
+}
+
+fn plan_swaps(items: &[char]) -> Result<Vec<(char, char)>, RepackOutcome> {
+    let mut counts = counts(items);
+    if !repackable(&counts, items.len() / 2) {
+        return Err(RepackOutcome::Impossible);
+    }
+
+    let mut failed = HashMap::new();
+    (0..=MAX_SWAPS)
+        .find_map(|depth| {
+            let mut plan = Vec::new();
+            search(&mut counts, depth, &mut plan, &mut failed).then_some(plan)
+        })
+        .ok_or(RepackOutcome::TooManySwaps)
+}
+
+pub fn repack(input: &str, scheme: &dyn PriorityScheme) -> Result<Vec<Repack>, RucksackError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let mut items = compartments(line, i + 1, scheme)?;
+            let half = items.len() / 2;
+            let plan = match plan_swaps(&items) {
+                Ok(plan) => plan,
+                Err(outcome) => {
+                    return Ok(Repack {
+                        line: i + 1,
+                        outcome,
+                    })
+                }
+            };
+
+            let mut swaps = Vec::new();
+            for (x, y) in plan {
+                let first = items[..half].iter().position(|&c| c == x).unwrap();
+                let second = half + items[half..].iter().position(|&c| c == y).unwrap();
+                items.swap(first, second);
+                swaps.push(Swap { first, second });
+            }
+
+            Ok(Repack {
+                line: i + 1,
+                outcome: RepackOutcome::Repacked {
+                    swaps,
+                    corrected: items.into_iter().collect(),
+                },
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RucksackError {
+    InvalidItem { line: usize, item: char },
+    OddLength { line: usize },
+    UnevenGroups { lines: usize, group_size: usize },
+    NoCommonItem { line: usize },
+    SeveralCommonItems { line: usize, priorities: Vec<u32> },
+}
+
+impl fmt::Display for RucksackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RucksackError::InvalidItem { line, item } => {
+                write!(f, "line {line}: {item:?} is not an item type")
+            }
+            RucksackError::OddLength { line } => {
+                write!(
+                    f,
+                    "line {line}: rucksack cannot be split into two equal compartments"
+                )
+            }
+            RucksackError::UnevenGroups { lines, group_size } => {
+                write!(
+                    f,
+                    "{lines} rucksacks cannot be split into groups of {group_size}"
+                )
+            }
+            RucksackError::NoCommonItem { line } => {
+                write!(f, "line {line}: no item type is shared")
+            }
+            RucksackError::SeveralCommonItems { line, priorities } => {
+                write!(
+                    f,
+                    "line {line}: several item types are shared, priorities {priorities:?}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RucksackError {}
@@ -0,0 +1,149 @@
+// Works out how rucksacks form elf groups when the input is not laid out in
+// consecutive groups: every group must share exactly one item type.
+
+use std::collections::HashSet;
+
+use crate::{str_to_set, ItemSet, PriorityScheme, RucksackError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Grouping {
+    // Groups are runs of adjacent lines.
+    Consecutive,
+    // Any lines may form a group.
+    Arbitrary,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Partition {
+    // Line numbers of each group's rucksacks.
+    pub groups: Vec<Vec<usize>>,
+    pub badges: Vec<u32>,
+    // Whether this is the only partition that works.
+    pub unique: bool,
+}
+
+struct Search<'a> {
+    sets: &'a [ItemSet],
+    group_size: usize,
+    grouping: Grouping,
+    assigned: Vec<bool>,
+    groups: Vec<Vec<usize>>,
+    found: Vec<Vec<Vec<usize>>>,
+    // Sets of assigned lines from which no partition exists.
+    dead_ends: HashSet<Vec<bool>>,
+}
+
+impl Search<'_> {
+    // Fills the group anchored at the first unassigned line, then recurses.
+    fn run(&mut self) {
+        let anchor = match self.assigned.iter().position(|&a| !a) {
+            Some(anchor) => anchor,
+            None => {
+                self.found.push(self.groups.clone());
+                return;
+            }
+        };
+        if self.dead_ends.contains(&self.assigned) {
+            return;
+        }
+
+        let before = self.found.len();
+        self.assigned[anchor] = true;
+        let sets = self.sets;
+        self.extend(vec![anchor], &sets[anchor]);
+        self.assigned[anchor] = false;
+
+        if self.found.len() == before {
+            self.dead_ends.insert(self.assigned.clone());
+        }
+    }
+
+    fn extend(&mut self, group: Vec<usize>, common: &ItemSet) {
+        if self.found.len() >= 2 {
+            return;
+        }
+        if group.len() == self.group_size {
+            if common.single().is_some() {
+                self.groups.push(group);
+                self.run();
+                self.groups.pop();
+            }
+            return;
+        }
+
+        let last = *group.last().unwrap();
+        let candidates = match self.grouping {
+            Grouping::Consecutive => last + 1..(last + 2).min(self.sets.len()),
+            Grouping::Arbitrary => last + 1..self.sets.len(),
+        };
+        for i in candidates {
+            if self.assigned[i] {
+                continue;
+            }
+            let next = common.intersection(&self.sets[i]);
+            if next.is_empty() {
+                continue;
+            }
+            self.assigned[i] = true;
+            let mut group = group.clone();
+            group.push(i);
+            self.extend(group, &next);
+            self.assigned[i] = false;
+        }
+    }
+}
+
+// Partitions the rucksacks into groups of `group_size` that each share exactly one
+// item type. Returns None when no such partition exists.
+pub fn discover_groups(
+    input: &str,
+    group_size: usize,
+    grouping: Grouping,
+    scheme: &dyn PriorityScheme,
+) -> Result<Option<Partition>, RucksackError> {
+    let sets = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| str_to_set(line, i + 1, scheme))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if group_size == 0 || sets.len() % group_size != 0 {
+        return Err(RucksackError::UnevenGroups {
+            lines: sets.len(),
+            group_size,
+        });
+    }
+
+    let mut search = Search {
+        sets: &sets,
+        group_size,
+        grouping,
+        assigned: vec![false; sets.len()],
+        groups: vec![],
+        found: vec![],
+        dead_ends: HashSet::new(),
+    };
+    search.run();
+
+    let unique = search.found.len() == 1;
+    Ok(search.found.into_iter().next().map(|groups| {
+        let badges = groups
+            .iter()
+            .map(|g| {
+                let common = g
+                    .iter()
+                    .fold(sets[g[0]].clone(), |acc, &i| acc.intersection(&sets[i]));
+                common.single().unwrap()
+            })
+            .collect();
+        let groups = groups
+            .into_iter()
+            .map(|g| g.into_iter().map(|i| i + 1).collect())
+            .collect();
+        Partition {
+            groups,
+            badges,
+            unique,
+        }
+    }))
+}
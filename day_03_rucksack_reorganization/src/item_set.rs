@@ -0,0 +1,76 @@
+use std::collections::BTreeSet;
+
+// A set of rucksack item types by priority. Priorities below 64 are kept as bits,
+// larger ones (custom alphabets, Unicode) in a sparse set.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ItemSet {
+    bits: u64,
+    sparse: BTreeSet<u32>,
+}
+
+impl ItemSet {
+    pub fn insert(&mut self, priority: u32) {
+        if priority < 64 {
+            self.bits |= 1 << priority;
+        } else {
+            self.sparse.insert(priority);
+        }
+    }
+
+    pub fn contains(&self, priority: u32) -> bool {
+        if priority < 64 {
+            self.bits & (1 << priority) != 0
+        } else {
+            self.sparse.contains(&priority)
+        }
+    }
+
+    pub fn len(&self) -> u32 {
+        self.bits.count_ones() + self.sparse.len() as u32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0 && self.sparse.is_empty()
+    }
+
+    pub fn intersection(&self, other: &ItemSet) -> ItemSet {
+        ItemSet {
+            bits: self.bits & other.bits,
+            sparse: self.sparse.intersection(&other.sparse).copied().collect(),
+        }
+    }
+
+    pub fn union(&self, other: &ItemSet) -> ItemSet {
+        ItemSet {
+            bits: self.bits | other.bits,
+            sparse: self.sparse.union(&other.sparse).copied().collect(),
+        }
+    }
+
+    pub fn priorities(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..64)
+            .filter(|&p| self.bits & (1 << p) != 0)
+            .chain(self.sparse.iter().copied())
+    }
+
+    // The priority of the only item in the set.
+    pub fn single(&self) -> Option<u32> {
+        if self.len() != 1 {
+            None
+        } else if self.bits != 0 {
+            Some(self.bits.trailing_zeros())
+        } else {
+            self.sparse.first().copied()
+        }
+    }
+}
+
+impl FromIterator<u32> for ItemSet {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut set = ItemSet::default();
+        for priority in iter {
+            set.insert(priority);
+        }
+        set
+    }
+}
@@ -1,47 +1,107 @@
-use std::collections::HashSet;
+mod audit;
+mod error;
+mod grouping;
+mod item_set;
+mod priority;
 
-fn str_to_set(s: &str) -> HashSet<char> {
-    let mut set = HashSet::new();
-    for c in s.chars() {
-        set.insert(c);
-    }
-    set
+pub use crate::audit::{audit, repack, Audit, Repack, RepackOutcome, SharedItem, Swap};
+pub use crate::error::RucksackError;
+pub use crate::grouping::{discover_groups, Grouping, Partition};
+pub use crate::item_set::ItemSet;
+pub use crate::priority::{AdventOfCode, Alphabet, DigitsAndSymbols, PriorityScheme, Unicode};
+
+fn char_to_priority(c: char, scheme: &dyn PriorityScheme) -> Result<u32, char> {
+    scheme.priority(c).ok_or(c)
+}
+
+fn str_to_set(s: &str, line: usize, scheme: &dyn PriorityScheme) -> Result<ItemSet, RucksackError> {
+    s.chars()
+        .map(|c| {
+            char_to_priority(c, scheme).map_err(|item| RucksackError::InvalidItem { line, item })
+        })
+        .collect()
 }
 
-fn char_to_priority(c: char) -> u32 {
-    if c >= 'a' && c <= 'z' {
-        c as u32 - 'a' as u32 + 1
-    } else {
-        c as u32 - 'A' as u32 + 27
+fn single_common(set: ItemSet, line: usize) -> Result<u32, RucksackError> {
+    match set.len() {
+        0 => Err(RucksackError::NoCommonItem { line }),
+        1 => Ok(set.single().unwrap()),
+        _ => Err(RucksackError::SeveralCommonItems {
+            line,
+            priorities: set.priorities().collect(),
+        }),
     }
 }
 
-pub fn part_one(input: &str) -> u32 {
+// The priority of the item type found in both compartments of each rucksack.
+pub fn misplaced_priorities(
+    input: &str,
+    scheme: &dyn PriorityScheme,
+) -> Result<Vec<u32>, RucksackError> {
     input
         .lines()
-        .map(|line| {
-            let n = line.len() / 2;
-            let first = str_to_set(&line[..n]);
-            let second = str_to_set(&line[n..]);
-            let common = first.iter().find(|c| second.contains(*c)).unwrap();
-            char_to_priority(*common)
+        .enumerate()
+        .map(|(i, line)| {
+            let count = line.chars().count();
+            if count % 2 != 0 {
+                return Err(RucksackError::OddLength { line: i + 1 });
+            }
+            let n = line
+                .char_indices()
+                .nth(count / 2)
+                .map_or(line.len(), |(n, _)| n);
+            let first = str_to_set(&line[..n], i + 1, scheme)?;
+            let second = str_to_set(&line[n..], i + 1, scheme)?;
+            single_common(first.intersection(&second), i + 1)
         })
-        .sum()
+        .collect()
 }
 
-pub fn part_two(input: &str) -> u32 {
-    let lines = input.lines().map(|line| str_to_set(line)).collect::<Vec<_>>();
-
-    lines
-        .chunks_exact(3)
-        .map(|v| {
-            let s1 = &v[0];
-            let s2 = &v[1];
-            let s3 = &v[2];
-            let common = s1.iter().find(|c| s2.contains(*c) && s3.contains(*c)).unwrap();
-            char_to_priority(*common)
+// The priority of the badge shared by each group of `group_size` consecutive
+// rucksacks. Errors point at the first line of the offending group.
+pub fn badge_priorities(
+    input: &str,
+    group_size: usize,
+    scheme: &dyn PriorityScheme,
+) -> Result<Vec<u32>, RucksackError> {
+    let sets = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| str_to_set(line, i + 1, scheme))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if group_size == 0 || sets.len() % group_size != 0 {
+        return Err(RucksackError::UnevenGroups {
+            lines: sets.len(),
+            group_size,
+        });
+    }
+
+    sets.chunks_exact(group_size)
+        .enumerate()
+        .map(|(i, group)| {
+            let common = group
+                .iter()
+                .fold(group[0].clone(), |acc, s| acc.intersection(s));
+            single_common(common, i * group_size + 1)
         })
-        .sum()
+        .collect()
+}
+
+pub fn part_one_with(input: &str, scheme: &dyn PriorityScheme) -> Result<u32, RucksackError> {
+    Ok(misplaced_priorities(input, scheme)?.iter().sum())
+}
+
+pub fn part_two_with(input: &str, scheme: &dyn PriorityScheme) -> Result<u32, RucksackError> {
+    Ok(badge_priorities(input, 3, scheme)?.iter().sum())
+}
+
+pub fn part_one(input: &str) -> u32 {
+    part_one_with(input, &AdventOfCode).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn part_two(input: &str) -> u32 {
+    part_two_with(input, &AdventOfCode).unwrap_or_else(|e| panic!("{e}"))
 }
 
 #[cfg(test)]
@@ -55,4 +115,171 @@ mod tests {
         assert_eq!(part_one(EXAMPLE), 157);
         assert_eq!(part_two(EXAMPLE), 70);
     }
+
+    #[test]
+    fn badge_priorities_works() {
+        assert_eq!(
+            badge_priorities(EXAMPLE, 3, &AdventOfCode),
+            Ok(vec![18, 52])
+        );
+        assert_eq!(
+            badge_priorities(EXAMPLE, 6, &AdventOfCode),
+            Err(RucksackError::NoCommonItem { line: 1 })
+        );
+        assert_eq!(
+            badge_priorities(EXAMPLE, 4, &AdventOfCode),
+            Err(RucksackError::UnevenGroups {
+                lines: 6,
+                group_size: 4
+            })
+        );
+        assert_eq!(
+            badge_priorities("abc\nabd\n", 2, &AdventOfCode),
+            Err(RucksackError::SeveralCommonItems {
+                line: 1,
+                priorities: vec![1, 2]
+            })
+        );
+        assert_eq!(
+            misplaced_priorities("abcab", &AdventOfCode),
+            Err(RucksackError::OddLength { line: 1 })
+        );
+        assert_eq!(
+            misplaced_priorities("aa\nc1", &AdventOfCode),
+            Err(RucksackError::InvalidItem { line: 2, item: '1' })
+        );
+
+        let set = "vJrwpWtwJgWr"
+            .chars()
+            .filter_map(|c| AdventOfCode.priority(c))
+            .collect::<ItemSet>();
+        assert_eq!(set.len(), 8);
+        assert!(set.contains(AdventOfCode.priority('W').unwrap()));
+
+        assert_eq!(
+            misplaced_priorities("éa", &AdventOfCode),
+            Err(RucksackError::InvalidItem {
+                line: 1, item: 'é'
+            })
+        );
+        assert_eq!(
+            misplaced_priorities("aéa", &AdventOfCode),
+            Err(RucksackError::OddLength { line: 1 })
+        );
+        let set = [1, 63, 64, 1000, u32::MAX].into_iter().collect::<ItemSet>();
+        assert_eq!(set.len(), 5);
+        assert!(set.contains(u32::MAX) && !set.contains(65));
+    }
+
+    #[test]
+    fn audit_works() {
+        let audits = audit(EXAMPLE, &AdventOfCode).unwrap();
+        assert!(audits.iter().all(|a| a.shared.len() == 1));
+        assert_eq!(
+            audits[0].shared,
+            vec![SharedItem {
+                item: 'p',
+                priority: 16,
+                first: 1,
+                second: 1
+            }]
+        );
+
+        let audits = audit("aabXbacX", &AdventOfCode).unwrap();
+        assert_eq!(
+            audits[0]
+                .shared
+                .iter()
+                .map(|s| (s.item, s.first, s.second))
+                .collect::<Vec<_>>(),
+            vec![('X', 1, 1), ('a', 2, 1), ('b', 1, 1)]
+        );
+
+        let wide = "abcdefghijklmnopqrs".repeat(2);
+        let repacks = repack(&format!("aabXbacX\nabcd\n{wide}\n"), &AdventOfCode).unwrap();
+        let RepackOutcome::Repacked { swaps, corrected } = &repacks[0].outcome else {
+            panic!("{:?}", repacks[0])
+        };
+        assert_eq!(swaps.len(), 1);
+        assert_eq!(audit(corrected, &AdventOfCode).unwrap()[0].shared.len(), 1);
+        assert_eq!(
+            repacks[1],
+            Repack {
+                line: 2,
+                outcome: RepackOutcome::Impossible
+            }
+        );
+        assert_eq!(
+            repacks[2],
+            Repack {
+                line: 3,
+                outcome: RepackOutcome::TooManySwaps
+            }
+        );
+
+        let repacks = repack(EXAMPLE, &AdventOfCode).unwrap();
+        assert!(repacks.iter().all(
+            |r| matches!(&r.outcome, RepackOutcome::Repacked { swaps, .. } if swaps.is_empty())
+        ));
+    }
+
+    #[test]
+    fn discover_groups_works() {
+        let partition = discover_groups(EXAMPLE, 3, Grouping::Consecutive, &AdventOfCode)
+            .unwrap()
+            .unwrap();
+        assert_eq!(partition.groups, vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        assert_eq!(partition.badges, vec![18, 52]);
+        assert!(partition.unique);
+
+        let lines = EXAMPLE.lines().collect::<Vec<_>>();
+        let shuffled = [0, 3, 1, 4, 2, 5].map(|i| lines[i]).join("\n");
+        assert_eq!(
+            discover_groups(&shuffled, 3, Grouping::Consecutive, &AdventOfCode).unwrap(),
+            None
+        );
+
+        let partition = discover_groups(&shuffled, 3, Grouping::Arbitrary, &AdventOfCode)
+            .unwrap()
+            .unwrap();
+        assert_eq!(partition.groups, vec![vec![1, 3, 5], vec![2, 4, 6]]);
+        assert_eq!(partition.badges, vec![18, 52]);
+
+        let partition = discover_groups("ab\nac\nbd\nbe\n", 2, Grouping::Arbitrary, &AdventOfCode)
+            .unwrap()
+            .unwrap();
+        assert!(partition.unique);
+        let partition = discover_groups("ab\nac\nab\nac\n", 2, Grouping::Arbitrary, &AdventOfCode)
+            .unwrap()
+            .unwrap();
+        assert!(!partition.unique);
+    }
+
+    #[test]
+    fn priority_schemes_work() {
+        assert_eq!(part_one_with(EXAMPLE, &DigitsAndSymbols), Ok(157));
+        assert_eq!(
+            part_two_with(EXAMPLE, &Unicode),
+            Ok('r' as u32 + 'Z' as u32)
+        );
+
+        assert_eq!(DigitsAndSymbols.priority('0'), Some(53));
+        assert_eq!(DigitsAndSymbols.priority('!'), Some(63));
+        assert_eq!(DigitsAndSymbols.priority('~'), Some(94));
+        assert_eq!(part_one_with("1a2b3c1d", &DigitsAndSymbols), Ok(54));
+        assert_eq!(
+            part_one_with("1a2b3c1d", &AdventOfCode),
+            Err(RucksackError::InvalidItem { line: 1, item: '1' })
+        );
+
+        assert_eq!(part_one_with("äöüäéè", &Unicode), Ok('ä' as u32));
+        assert_eq!(part_two_with("αβ\nβγ\nδβ", &Unicode), Ok('β' as u32));
+
+        let alphabet = Alphabet::from("zyx wvu");
+        assert_eq!(part_one_with("zyzv", &alphabet), Ok(1));
+        assert_eq!(
+            part_one_with("zaza", &alphabet),
+            Err(RucksackError::InvalidItem { line: 1, item: 'a' })
+        );
+    }
 }
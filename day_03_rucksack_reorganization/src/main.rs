@@ -1,8 +1,118 @@
-use day_03_rucksack_reorganization::{part_one, part_two};
+use day_03_rucksack_reorganization::{
+    audit, discover_groups, part_one_with, part_two_with, repack, AdventOfCode, Alphabet,
+    DigitsAndSymbols, Grouping, PriorityScheme, RepackOutcome, Unicode,
+};
 
 const INPUT: &str = include_str!("../data/input.txt");
 
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("{message}");
+    std::process::exit(1);
+}
+
+// Takes `--scheme aoc|digits|unicode|alphabet=PATH` out of the arguments, defaulting
+// to the Advent of Code priorities.
+fn scheme(args: &mut Vec<String>) -> Box<dyn PriorityScheme> {
+    let Some(i) = args.iter().position(|a| a == "--scheme") else {
+        return Box::new(AdventOfCode);
+    };
+    let Some(name) = args.get(i + 1) else {
+        fail("--scheme needs a value: aoc, digits, unicode or alphabet=PATH");
+    };
+    let scheme: Box<dyn PriorityScheme> = match name.split_once('=') {
+        None if name == "aoc" => Box::new(AdventOfCode),
+        None if name == "digits" => Box::new(DigitsAndSymbols),
+        None if name == "unicode" => Box::new(Unicode),
+        Some(("alphabet", path)) => Box::new(
+            Alphabet::load(path).unwrap_or_else(|e| fail(format!("cannot read {path}: {e}"))),
+        ),
+        _ => fail(format!(
+            "unknown scheme {name:?}, expected aoc, digits, unicode or alphabet=PATH"
+        )),
+    };
+    args.drain(i..i + 2);
+    scheme
+}
+
 fn main() {
-    println!("part one answer: {}", part_one(INPUT));
-    println!("part two answer: {}", part_two(INPUT));
+    let mut args = std::env::args().collect::<Vec<_>>();
+    let scheme = scheme(&mut args);
+    let scheme = scheme.as_ref();
+
+    if args.get(1).map(String::as_str) == Some("audit") {
+        let input = match args.get(2) {
+            Some(path) => std::fs::read_to_string(path)
+                .unwrap_or_else(|e| fail(format!("cannot read {path}: {e}"))),
+            None => INPUT.to_string(),
+        };
+        let audits = audit(&input, scheme).unwrap_or_else(|e| fail(e));
+        let repacks = repack(&input, scheme).unwrap_or_else(|e| fail(e));
+        for (a, r) in audits.iter().zip(repacks.iter()) {
+            if a.shared.len() == 1 {
+                continue;
+            }
+            let shared = a
+                .shared
+                .iter()
+                .map(|s| format!("{} ({}/{})", s.item, s.first, s.second))
+                .collect::<Vec<_>>()
+                .join(", ");
+            println!(
+                "line {}: shared {}",
+                a.line,
+                if shared.is_empty() {
+                    "nothing"
+                } else {
+                    &shared
+                }
+            );
+            match &r.outcome {
+                RepackOutcome::Repacked { swaps, corrected } => {
+                    let swaps = swaps
+                        .iter()
+                        .map(|s| format!("{}<->{}", s.first, s.second))
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    println!("  swap {swaps}: {corrected}");
+                }
+                RepackOutcome::Impossible => println!("  no repack possible"),
+                RepackOutcome::TooManySwaps => println!("  no repack within the swap limit"),
+            }
+        }
+        return;
+    }
+
+    if args.get(1).map(String::as_str) == Some("groups") {
+        let grouping = match args.get(2).map(String::as_str) {
+            Some("arbitrary") => Grouping::Arbitrary,
+            _ => Grouping::Consecutive,
+        };
+        let group_size = args.get(3).map_or(3, |s| {
+            s.parse()
+                .unwrap_or_else(|_| fail(format!("invalid group size {s:?}")))
+        });
+        match discover_groups(INPUT, group_size, grouping, scheme).unwrap_or_else(|e| fail(e)) {
+            Some(partition) => {
+                for (group, badge) in partition.groups.iter().zip(partition.badges.iter()) {
+                    println!("{group:?}: badge priority {badge}");
+                }
+                println!(
+                    "badge priority sum: {}",
+                    partition.badges.iter().sum::<u32>()
+                );
+                println!("unique: {}", partition.unique);
+            }
+            None => println!("no grouping shares exactly one item type per group"),
+        }
+        return;
+    }
+
+    println!(
+        "part one answer: {}",
+        part_one_with(INPUT, scheme).unwrap_or_else(|e| fail(e))
+    );
+    println!(
+        "part two answer: {}",
+        part_two_with(INPUT, scheme).unwrap_or_else(|e| fail(e))
+    );
 }
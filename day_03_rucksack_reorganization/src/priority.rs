@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+// Maps rucksack items to priorities. Items without a priority are not valid items.
+pub trait PriorityScheme {
+    fn priority(&self, item: char) -> Option<u32>;
+}
+
+// a-z are 1 through 26, A-Z are 27 through 52.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AdventOfCode;
+
+impl PriorityScheme for AdventOfCode {
+    fn priority(&self, item: char) -> Option<u32> {
+        match item {
+            'a'..='z' => Some(item as u32 - 'a' as u32 + 1),
+            'A'..='Z' => Some(item as u32 - 'A' as u32 + 27),
+            _ => None,
+        }
+    }
+}
+
+// The Advent of Code letters, then 0-9 as 53 through 62, then the printable ASCII
+// symbols in code point order from 63.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DigitsAndSymbols;
+
+impl PriorityScheme for DigitsAndSymbols {
+    fn priority(&self, item: char) -> Option<u32> {
+        match item {
+            '0'..='9' => Some(item as u32 - '0' as u32 + 53),
+            c if c.is_ascii_punctuation() => {
+                let rank = ('!'..c).filter(|s| s.is_ascii_punctuation()).count() as u32;
+                Some(63 + rank)
+            }
+            c => AdventOfCode.priority(c),
+        }
+    }
+}
+
+// Every Unicode scalar value is an item, with its code point as priority.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Unicode;
+
+impl PriorityScheme for Unicode {
+    fn priority(&self, item: char) -> Option<u32> {
+        Some(item as u32)
+    }
+}
+
+// A custom ordered alphabet: the first item has priority 1, the next 2, and so on.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Alphabet {
+    priorities: HashMap<char, u32>,
+}
+
+impl Alphabet {
+    pub fn load(path: &str) -> std::io::Result<Self> {
+        Ok(Alphabet::from(std::fs::read_to_string(path)?.as_str()))
+    }
+}
+
+// Whitespace is ignored; an item listed twice keeps its first priority.
+impl From<&str> for Alphabet {
+    fn from(s: &str) -> Self {
+        let mut priorities = HashMap::new();
+        for c in s.chars().filter(|c| !c.is_whitespace()) {
+            let next = priorities.len() as u32 + 1;
+            priorities.entry(c).or_insert(next);
+        }
+        Self { priorities }
+    }
+}
+
+impl PriorityScheme for Alphabet {
+    fn priority(&self, item: char) -> Option<u32> {
+        self.priorities.get(&item).copied()
+    }
+}
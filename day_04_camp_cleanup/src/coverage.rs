@@ -0,0 +1,61 @@
+// How the elves' assignments cover the sections between them.
+
+use crate::{elves, IntervalSet, SectionRange};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Coverage {
+    pub span: SectionRange,
+    // Sections assigned to at least one elf.
+    pub covered: IntervalSet,
+    // Sections in the span assigned to nobody.
+    pub uncovered: IntervalSet,
+    // Sections assigned to two or more elves.
+    pub shared: IntervalSet,
+}
+
+// Sections covered by at least `k` of the ranges, found with a sweep over range ends.
+pub fn covered_at_least(ranges: &[SectionRange], k: usize) -> IntervalSet {
+    let mut events = ranges
+        .iter()
+        .flat_map(|r| [(r.min as u64, 1), (r.max as u64 + 1, -1)])
+        .collect::<Vec<(u64, i64)>>();
+    events.sort_unstable();
+
+    let mut set = IntervalSet::default();
+    let mut depth = 0;
+    let mut start = 0;
+    for (at, delta) in events {
+        let was = depth >= k as i64;
+        depth += delta;
+        let is = depth >= k as i64;
+        if !was && is {
+            start = at;
+        } else if was && !is && at > start {
+            set.insert(SectionRange::new(start as u32, (at - 1) as u32));
+        }
+    }
+    set
+}
+
+// Coverage of every elf in the input within `span`, which defaults to the lowest to
+// highest assigned section. Returns None for empty input without a span.
+pub fn coverage(input: &str, span: Option<SectionRange>) -> Option<Coverage> {
+    let ranges = elves(input).iter().map(|e| e.range).collect::<Vec<_>>();
+    let span = span.or_else(|| {
+        let min = ranges.iter().map(|r| r.min).min()?;
+        let max = ranges.iter().map(|r| r.max).max()?;
+        Some(SectionRange::new(min, max))
+    })?;
+
+    let covered = ranges.iter().copied().collect::<IntervalSet>();
+    let uncovered = [span]
+        .into_iter()
+        .collect::<IntervalSet>()
+        .difference(&covered);
+    Some(Coverage {
+        span,
+        covered,
+        uncovered,
+        shared: covered_at_least(&ranges, 2),
+    })
+}
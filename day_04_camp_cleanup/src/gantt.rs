@@ -0,0 +1,71 @@
+// Draws assignments as strips over a shared section axis, like the puzzle's
+// `.234.....` diagrams: `=` is an assigned section and `#` one that another elf on
+// the same line is also assigned.
+
+use std::fmt::Write;
+
+use crate::{covered_at_least, parse_groups, SectionRange};
+
+// Draws the given 1-based input lines, or all of them, at most `width` columns wide.
+// Each column covers the same number of sections when the span is wider than that.
+pub fn gantt(input: &str, lines: Option<&[usize]>, width: usize) -> String {
+    let groups = parse_groups(input)
+        .unwrap_or_else(|e| panic!("{e}"))
+        .into_iter()
+        .enumerate()
+        .map(|(i, group)| (i + 1, group))
+        .filter(|(line, _)| lines.is_none_or(|lines| lines.contains(line)))
+        .collect::<Vec<_>>();
+    let ranges = groups.iter().flat_map(|(_, g)| g);
+    let (Some(min), Some(max)) = (
+        ranges.clone().map(|r| r.min).min(),
+        ranges.map(|r| r.max).max(),
+    ) else {
+        return String::new();
+    };
+
+    let span = SectionRange::new(min, max).length();
+    let scale = span.div_ceil(width.max(1) as u64);
+    let columns = span.div_ceil(scale) as usize;
+    let column = |c: usize| {
+        let start = min as u64 + c as u64 * scale;
+        SectionRange::new(start as u32, (start + scale - 1).min(max as u64) as u32)
+    };
+    let label = groups.last().map_or(1, |(line, _)| line.to_string().len()) + 3;
+
+    let mut s = String::new();
+    let mut numbers = " ".repeat(label);
+    let mut ticks = " ".repeat(label);
+    for c in 0..columns {
+        if c % 10 == 0 {
+            if numbers.len() <= label + c {
+                numbers.truncate(label + c);
+                numbers.extend(std::iter::repeat_n(' ', label + c - numbers.len()));
+                write!(numbers, "{}", column(c).min).unwrap();
+            }
+            ticks.push('+');
+        } else {
+            ticks.push('-');
+        }
+    }
+    writeln!(s, "{}", numbers.trim_end()).unwrap();
+    writeln!(s, "{ticks}").unwrap();
+
+    for (line, group) in &groups {
+        let shared = covered_at_least(group, 2);
+        for (position, range) in group.iter().enumerate() {
+            let strip = (0..columns)
+                .map(|c| {
+                    let cell = column(c);
+                    match cell.intersection(*range) {
+                        None => '.',
+                        Some(part) if shared.ranges().iter().any(|r| r.overlaps(part)) => '#',
+                        Some(_) => '=',
+                    }
+                })
+                .collect::<String>();
+            writeln!(s, "{:>w$}.{} {strip}", line, position + 1, w = label - 3).unwrap();
+        }
+    }
+    s
+}
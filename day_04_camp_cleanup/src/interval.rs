@@ -0,0 +1,138 @@
+// Closed section ranges and normalised sets of them.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SectionRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl SectionRange {
+    pub fn new(min: u32, max: u32) -> Self {
+        assert!(min <= max, "empty section range {min}-{max}");
+        Self { min, max }
+    }
+
+    // Number of sections in the range.
+    pub fn length(&self) -> u64 {
+        (self.max - self.min) as u64 + 1
+    }
+
+    pub fn contains(&self, sr: SectionRange) -> bool {
+        self.min <= sr.min && self.max >= sr.max
+    }
+
+    pub fn overlaps(&self, sr: SectionRange) -> bool {
+        self.min <= sr.max && sr.min <= self.max
+    }
+
+    // Whether the ranges touch end to end without sharing a section.
+    pub fn is_adjacent(&self, sr: SectionRange) -> bool {
+        self.max.checked_add(1) == Some(sr.min) || sr.max.checked_add(1) == Some(self.min)
+    }
+
+    pub fn intersection(&self, sr: SectionRange) -> Option<SectionRange> {
+        self.overlaps(sr)
+            .then(|| SectionRange::new(self.min.max(sr.min), self.max.min(sr.max)))
+    }
+
+    // The single range covering both, if they overlap or are adjacent.
+    pub fn union(&self, sr: SectionRange) -> Option<SectionRange> {
+        (self.overlaps(sr) || self.is_adjacent(sr))
+            .then(|| SectionRange::new(self.min.min(sr.min), self.max.max(sr.max)))
+    }
+
+    // The sections of `self` not in `sr`: none, one or two ranges.
+    pub fn difference(&self, sr: SectionRange) -> Vec<SectionRange> {
+        if !self.overlaps(sr) {
+            return vec![*self];
+        }
+        let mut pieces = vec![];
+        if self.min < sr.min {
+            pieces.push(SectionRange::new(self.min, sr.min - 1));
+        }
+        if self.max > sr.max {
+            pieces.push(SectionRange::new(sr.max + 1, self.max));
+        }
+        pieces
+    }
+}
+
+// Sorted ranges that neither overlap nor touch.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<SectionRange>,
+}
+
+impl IntervalSet {
+    pub fn ranges(&self) -> &[SectionRange] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // Number of sections in the set.
+    pub fn len(&self) -> u64 {
+        self.ranges.iter().map(|r| r.length()).sum()
+    }
+
+    pub fn contains(&self, section: u32) -> bool {
+        let i = self.ranges.partition_point(|r| r.max < section);
+        self.ranges.get(i).is_some_and(|r| r.min <= section)
+    }
+
+    pub fn insert(&mut self, sr: SectionRange) {
+        let start = self
+            .ranges
+            .partition_point(|r| r.max < sr.min && !r.is_adjacent(sr));
+        let mut merged = sr;
+        let mut end = start;
+        while let Some(u) = self.ranges.get(end).and_then(|r| r.union(merged)) {
+            merged = u;
+            end += 1;
+        }
+        self.ranges.splice(start..end, [merged]);
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut set = self.clone();
+        for &r in &other.ranges {
+            set.insert(r);
+        }
+        set
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let (mut i, mut j) = (0, 0);
+        let mut ranges = vec![];
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (self.ranges[i], other.ranges[j]);
+            ranges.extend(a.intersection(b));
+            if a.max < b.max {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = self.ranges.clone();
+        for &cut in &other.ranges {
+            ranges = ranges.iter().flat_map(|r| r.difference(cut)).collect();
+        }
+        IntervalSet { ranges }
+    }
+}
+
+impl FromIterator<SectionRange> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = SectionRange>>(iter: I) -> Self {
+        let mut set = IntervalSet::default();
+        for sr in iter {
+            set.insert(sr);
+        }
+        set
+    }
+}
@@ -1,52 +1,72 @@
-#[derive(Clone, Copy)]
-struct SectionRange {
-    min: u32,
-    max: u32,
+mod coverage;
+mod gantt;
+mod interval;
+mod parse;
+mod release;
+mod sweep;
+
+pub use crate::coverage::{coverage, covered_at_least, Coverage};
+pub use crate::gantt::gantt;
+pub use crate::interval::{IntervalSet, SectionRange};
+pub use crate::parse::{parse_group, parse_groups, parse_range, ParseError};
+pub use crate::release::{release_fewest, release_weighted, Release};
+pub use crate::sweep::{overlaps, Overlaps};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Elf {
+    pub line: usize,
+    // Position of the range within its line, from 0.
+    pub position: usize,
+    pub range: SectionRange,
 }
 
-impl SectionRange {
-    fn contains(&self, sr: SectionRange) -> bool {
-        self.min <= sr.min && self.max >= sr.max
-    }
+fn groups(input: &str) -> Vec<Vec<SectionRange>> {
+    parse_groups(input).unwrap_or_else(|e| panic!("{e}"))
+}
 
-    fn overlap(&self, sr: SectionRange) -> bool {
-        (self.min >= sr.min && self.min <= sr.max) ||
-        (self.max >= sr.min && self.max <= sr.max)
-    }
+// Every elf in input order.
+pub fn elves(input: &str) -> Vec<Elf> {
+    groups(input)
+        .into_iter()
+        .enumerate()
+        .flat_map(|(i, group)| {
+            group
+                .into_iter()
+                .enumerate()
+                .map(move |(position, range)| Elf {
+                    line: i + 1,
+                    position,
+                    range,
+                })
+        })
+        .collect()
 }
 
-impl From<&str> for SectionRange {
-    fn from(s: &str) -> Self {
-        let (min, max) = s.split_once('-').unwrap();
-        Self {
-            min: min.parse().unwrap(),
-            max: max.parse().unwrap(),
-        }
-    }
+// Whether some range in the group fully contains another one.
+pub fn any_contains(group: &[SectionRange]) -> bool {
+    let mut sorted = group.to_vec();
+    sorted.sort_by_key(|r| (r.min, std::cmp::Reverse(r.max)));
+    sorted.windows(2).any(|w| w[0].contains(w[1]))
+}
+
+// Whether any two ranges in the group overlap.
+pub fn any_overlap(group: &[SectionRange]) -> bool {
+    let mut sorted = group.to_vec();
+    sorted.sort_by_key(|r| r.min);
+    let mut max = None;
+    sorted.iter().any(|r| {
+        let hit = max.is_some_and(|max| r.min <= max);
+        max = max.max(Some(r.max));
+        hit
+    })
 }
 
 pub fn part_one(input: &str) -> usize {
-    input
-        .lines()
-        .filter(|line| {
-            let (sr1, sr2) = line.split_once(',').unwrap();
-            let sr1 = SectionRange::from(sr1);
-            let sr2 = SectionRange::from(sr2);
-            sr1.contains(sr2) || sr2.contains(sr1)
-        })
-        .count()
+    groups(input).iter().filter(|g| any_contains(g)).count()
 }
 
 pub fn part_two(input: &str) -> usize {
-    input
-        .lines()
-        .filter(|line| {
-            let (sr1, sr2) = line.split_once(',').unwrap();
-            let sr1 = SectionRange::from(sr1);
-            let sr2 = SectionRange::from(sr2);
-            sr1.overlap(sr2) || sr2.overlap(sr1)
-        })
-        .count()
+    groups(input).iter().filter(|g| any_overlap(g)).count()
 }
 
 #[cfg(test)]
@@ -60,4 +80,201 @@ mod tests {
         assert_eq!(part_one(EXAMPLE), 2);
         assert_eq!(part_two(EXAMPLE), 4);
     }
+
+    #[test]
+    fn interval_algebra_works() {
+        let a = SectionRange::new(2, 6);
+        let b = SectionRange::new(4, 8);
+        let c = SectionRange::new(7, 9);
+        assert_eq!(a.length(), 5);
+        assert_eq!(a.intersection(b), Some(SectionRange::new(4, 6)));
+        assert_eq!(a.intersection(c), None);
+        assert_eq!(a.union(b), Some(SectionRange::new(2, 8)));
+        assert_eq!(a.union(c), Some(SectionRange::new(2, 9)));
+        assert!(a.is_adjacent(c) && !a.is_adjacent(b));
+        assert_eq!(b.union(SectionRange::new(10, 11)), None);
+        assert_eq!(
+            a.difference(SectionRange::new(3, 4)),
+            vec![SectionRange::new(2, 2), SectionRange::new(5, 6)]
+        );
+        assert_eq!(a.difference(SectionRange::new(1, 9)), vec![]);
+
+        let set = [
+            SectionRange::new(10, 12),
+            a,
+            SectionRange::new(13, 14),
+            SectionRange::new(1, 1),
+        ]
+        .into_iter()
+        .collect::<IntervalSet>();
+        assert_eq!(
+            set.ranges(),
+            &[SectionRange::new(1, 6), SectionRange::new(10, 14)]
+        );
+        assert_eq!(set.len(), 11);
+        assert!(set.contains(12) && !set.contains(8));
+        let other = [b].into_iter().collect::<IntervalSet>();
+        assert_eq!(
+            set.intersection(&other).ranges(),
+            &[SectionRange::new(4, 6)]
+        );
+        assert_eq!(
+            set.union(&other).ranges(),
+            &[SectionRange::new(1, 8), SectionRange::new(10, 14)]
+        );
+        assert_eq!(
+            set.difference(&other).ranges(),
+            &[SectionRange::new(1, 3), SectionRange::new(10, 14)]
+        );
+    }
+
+    #[test]
+    fn coverage_works() {
+        let c = coverage(EXAMPLE, None).unwrap();
+        assert_eq!(c.span, SectionRange::new(2, 9));
+        assert_eq!(c.covered.len(), 8);
+        assert!(c.uncovered.is_empty());
+        assert_eq!(c.shared.ranges(), &[SectionRange::new(2, 8)]);
+
+        let c = coverage("2-3,6-7", Some(SectionRange::new(1, 9))).unwrap();
+        assert_eq!(
+            c.uncovered.ranges(),
+            &[
+                SectionRange::new(1, 1),
+                SectionRange::new(4, 5),
+                SectionRange::new(8, 9)
+            ]
+        );
+        assert!(c.shared.is_empty());
+        assert_eq!(coverage("", None), None);
+    }
+
+    #[test]
+    fn overlaps_works() {
+        let o = overlaps(EXAMPLE);
+        assert_eq!(o.max_depth, 8);
+        assert_eq!(o.busiest, Some(6));
+        let all = elves(EXAMPLE);
+        let brute = (0..all.len())
+            .flat_map(|i| (i + 1..all.len()).map(move |j| (i, j)))
+            .filter(|&(i, j)| all[i].range.overlaps(all[j].range))
+            .count();
+        assert_eq!(o.pairs.len(), brute);
+        let lines = |a: &Elf, b: &Elf| (a.line, b.line);
+        assert!(o
+            .pairs
+            .iter()
+            .any(|(a, b)| lines(a, b) == (1, 2) || lines(a, b) == (2, 1)));
+        assert!(!o
+            .pairs
+            .iter()
+            .any(|(a, b)| (a.line, a.position, b.line, b.position) == (1, 0, 1, 1)));
+
+        let o = overlaps("1-2,5-6\n3-4,7-8");
+        assert!(o.pairs.is_empty());
+        assert_eq!((o.max_depth, o.busiest), (1, Some(1)));
+        assert_eq!(overlaps("").busiest, None);
+    }
+
+    #[test]
+    fn release_works() {
+        let r = release_fewest(EXAMPLE);
+        let kept = r.kept.iter().map(|e| e.range).collect::<Vec<_>>();
+        assert_eq!(
+            kept,
+            vec![
+                SectionRange::new(2, 3),
+                SectionRange::new(4, 5),
+                SectionRange::new(7, 9),
+                SectionRange::new(6, 6)
+            ]
+        );
+        assert_eq!(r.released.len(), 8);
+        assert_eq!(r.coverage.ranges(), &[SectionRange::new(2, 9)]);
+
+        let r = release_weighted(EXAMPLE);
+        assert_eq!(r.coverage.len(), 8);
+        assert_eq!(r.kept.len() + r.released.len(), 12);
+        assert!(
+            covered_at_least(&r.kept.iter().map(|e| e.range).collect::<Vec<_>>(), 2).is_empty()
+        );
+
+        let r = release_weighted("1-10,2-3\n4-5,6-6");
+        assert_eq!(
+            r.kept
+                .iter()
+                .map(|e| (e.line, e.position))
+                .collect::<Vec<_>>(),
+            vec![(1, 0)]
+        );
+        assert_eq!(release_fewest("1-10,2-3\n4-5,6-6").kept.len(), 3);
+    }
+
+    #[test]
+    fn groups_work() {
+        assert_eq!(parse_range("[2,4)"), Ok(SectionRange::new(2, 3)));
+        assert_eq!(parse_range("(3,9]"), Ok(SectionRange::new(4, 9)));
+        assert_eq!(parse_range(" (3, 5) "), Ok(SectionRange::new(4, 4)));
+        assert_eq!(parse_range("[1,1]"), Ok(SectionRange::new(1, 1)));
+        assert!(parse_range("[2,2)").is_err());
+        assert!(parse_range("5-2").is_err());
+        assert!(parse_range("(0,x]").is_err());
+
+        let input = "2-4,[1,9),(5,7]\n1-2\n[1,3),3-4,(3,6]\n1-3,2-5,3-3";
+        assert_eq!(
+            parse_groups(input).unwrap()[0],
+            vec![
+                SectionRange::new(2, 4),
+                SectionRange::new(1, 8),
+                SectionRange::new(6, 7)
+            ]
+        );
+        assert_eq!(part_one(input), 2);
+        assert_eq!(part_two(input), 3);
+        assert_eq!(
+            parse_groups("1-2,3-4\n1-2;3-4"),
+            Err(ParseError {
+                line: 2,
+                message: "expected a section number, found \"2;3-4\"".to_string()
+            })
+        );
+        assert_eq!(
+            parse_groups("2-4,6-8\n5-3,1-2"),
+            Err(ParseError {
+                line: 2,
+                message: "\"5-3\" contains no sections".to_string()
+            })
+        );
+
+        // Only the middle range contains another, and it is not next to it by start.
+        assert!(any_contains(&[
+            SectionRange::new(1, 2),
+            SectionRange::new(2, 9),
+            SectionRange::new(3, 4)
+        ]));
+        assert!(!any_overlap(&[
+            SectionRange::new(5, 6),
+            SectionRange::new(1, 2),
+            SectionRange::new(3, 4)
+        ]));
+    }
+
+    #[test]
+    fn gantt_works() {
+        let input = "2-4,6-8\n5-7,7-9";
+        assert_eq!(
+            gantt(input, None, 80),
+            "    2\n    +-------\n1.1 ===.....\n1.2 ....===.\n2.1 ...==#..\n2.2 .....#==\n"
+        );
+        assert_eq!(
+            gantt(input, Some(&[2]), 80),
+            "    5\n    +----\n2.1 ==#..\n2.2 ..#==\n"
+        );
+        assert_eq!(gantt(input, Some(&[3]), 80), "");
+
+        // Two sections per column: 1-2, 3-4, ..., 19-20.
+        let chart = gantt("1-4,4-20", None, 10);
+        assert_eq!(chart.lines().nth(2), Some("1.1 =#........"));
+        assert_eq!(chart.lines().nth(3), Some("1.2 .#========"));
+    }
 }
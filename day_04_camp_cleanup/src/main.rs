@@ -1,8 +1,78 @@
-use day_04_camp_cleanup::{part_one, part_two};
+use day_04_camp_cleanup::{
+    coverage, gantt, overlaps, part_one, part_two, release_fewest, release_weighted,
+};
 
 const INPUT: &str = include_str!("../data/input.txt");
 
 fn main() {
+    if std::env::args().nth(1).as_deref() == Some("coverage") {
+        let c = coverage(INPUT, None).expect("no assignments");
+        println!("span: {}-{}", c.span.min, c.span.max);
+        println!("sections covered: {}", c.covered.len());
+        println!("sections covered by nobody: {}", c.uncovered.len());
+        println!("sections covered by two or more: {}", c.shared.len());
+        return;
+    }
+
+    if std::env::args().nth(1).as_deref() == Some("overlaps") {
+        let o = overlaps(INPUT);
+        for (a, b) in &o.pairs {
+            println!(
+                "line {} elf {} overlaps line {} elf {}",
+                a.line,
+                a.position + 1,
+                b.line,
+                b.position + 1
+            );
+        }
+        println!("overlapping pairs: {}", o.pairs.len());
+        match o.busiest {
+            Some(section) => println!("busiest section: {section} with {} elves", o.max_depth),
+            None => println!("no assignments"),
+        }
+        return;
+    }
+
+    if std::env::args().nth(1).as_deref() == Some("release") {
+        let release = match std::env::args().nth(2).as_deref() {
+            Some("weighted") => release_weighted(INPUT),
+            _ => release_fewest(INPUT),
+        };
+        for e in &release.released {
+            println!(
+                "release line {} elf {} ({}-{})",
+                e.line,
+                e.position + 1,
+                e.range.min,
+                e.range.max
+            );
+        }
+        println!(
+            "released {} of {} elves",
+            release.released.len(),
+            release.released.len() + release.kept.len()
+        );
+        let ranges = release
+            .coverage
+            .ranges()
+            .iter()
+            .map(|r| format!("{}-{}", r.min, r.max))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!("coverage: {} sections ({ranges})", release.coverage.len());
+        return;
+    }
+
+    if std::env::args().nth(1).as_deref() == Some("gantt") {
+        let lines = std::env::args()
+            .skip(2)
+            .map(|s| s.parse().unwrap())
+            .collect::<Vec<usize>>();
+        let lines = (!lines.is_empty()).then_some(lines.as_slice());
+        print!("{}", gantt(INPUT, lines, 100));
+        return;
+    }
+
     println!("part one answer: {}", part_one(INPUT));
     println!("part two answer: {}", part_two(INPUT));
 }
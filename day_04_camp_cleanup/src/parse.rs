@@ -0,0 +1,94 @@
+// Reads groups of any number of comma-separated ranges per line. A range is either
+// `2-4` or interval notation with closed or open ends, such as `[2,4)` or `(3,9]`.
+
+use std::fmt;
+
+use crate::SectionRange;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn parse_bound(s: &str) -> Result<u32, String> {
+    s.trim()
+        .parse()
+        .map_err(|_| format!("expected a section number, found {:?}", s.trim()))
+}
+
+pub fn parse_range(s: &str) -> Result<SectionRange, String> {
+    let s = s.trim();
+    let (min, max) = match (s.chars().next(), s.chars().last()) {
+        (Some(open @ ('[' | '(')), Some(close @ (']' | ')'))) if s.len() >= 2 => {
+            let (min, max) = s[1..s.len() - 1]
+                .split_once(',')
+                .ok_or_else(|| format!("expected two bounds in {s:?}"))?;
+            let (min, max) = (parse_bound(min)?, parse_bound(max)?);
+            let min = if open == '(' {
+                min.checked_add(1)
+            } else {
+                Some(min)
+            };
+            let max = if close == ')' {
+                max.checked_sub(1)
+            } else {
+                Some(max)
+            };
+            (min, max)
+        }
+        _ => {
+            let (min, max) = s
+                .split_once('-')
+                .ok_or_else(|| format!("expected a range like 2-4, found {s:?}"))?;
+            (Some(parse_bound(min)?), Some(parse_bound(max)?))
+        }
+    };
+    match (min, max) {
+        (Some(min), Some(max)) if min <= max => Ok(SectionRange::new(min, max)),
+        _ => Err(format!("{s:?} contains no sections")),
+    }
+}
+
+// Splits on the commas that are not inside interval brackets.
+fn split_ranges(line: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in line.char_indices() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&line[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&line[start..]);
+    parts
+}
+
+pub fn parse_group(line: &str, n: usize) -> Result<Vec<SectionRange>, ParseError> {
+    split_ranges(line)
+        .into_iter()
+        .map(|s| parse_range(s).map_err(|message| ParseError { line: n, message }))
+        .collect()
+}
+
+pub fn parse_groups(input: &str) -> Result<Vec<Vec<SectionRange>>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_group(line, i + 1))
+        .collect()
+}
@@ -0,0 +1,81 @@
+// Chooses which elves to release so that no section is cleaned twice.
+
+use std::cmp::Reverse;
+
+use crate::{elves, Elf, IntervalSet};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Release {
+    // Both in input order.
+    pub kept: Vec<Elf>,
+    pub released: Vec<Elf>,
+    // Sections still covered by the kept elves.
+    pub coverage: IntervalSet,
+}
+
+impl Release {
+    fn new(elves: Vec<Elf>, keep: &[bool]) -> Self {
+        let (kept, released): (Vec<_>, Vec<_>) =
+            elves.into_iter().zip(keep).partition(|&(_, &k)| k);
+        let kept = kept.into_iter().map(|(e, _)| e).collect::<Vec<_>>();
+        Self {
+            coverage: kept.iter().map(|e| e.range).collect(),
+            released: released.into_iter().map(|(e, _)| e).collect(),
+            kept,
+        }
+    }
+}
+
+// Elf indices ordered by last section. Among equal ends the shortest range comes
+// first, then input order, so every choice is deterministic.
+fn by_end(elves: &[Elf]) -> Vec<usize> {
+    let mut order = (0..elves.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| (elves[i].range.max, Reverse(elves[i].range.min), i));
+    order
+}
+
+// Releases as few elves as possible. Keeping the compatible elf that finishes first
+// is optimal: any optimal plan can swap its first elf for that one.
+pub fn release_fewest(input: &str) -> Release {
+    let elves = elves(input);
+    let mut keep = vec![false; elves.len()];
+    let mut end = None;
+    for i in by_end(&elves) {
+        if end.is_none_or(|end| elves[i].range.min > end) {
+            keep[i] = true;
+            end = Some(elves[i].range.max);
+        }
+    }
+    Release::new(elves, &keep)
+}
+
+// Releases elves so that the kept ones cover as many sections as possible, by
+// weighted interval scheduling over the elves ordered by last section.
+pub fn release_weighted(input: &str) -> Release {
+    let elves = elves(input);
+    let order = by_end(&elves);
+    let ends = order
+        .iter()
+        .map(|&i| elves[i].range.max)
+        .collect::<Vec<_>>();
+
+    // best[j] is the most sections the first j elves in `order` can keep.
+    let mut best = vec![0u64; order.len() + 1];
+    for (j, &i) in order.iter().enumerate() {
+        let before = ends.partition_point(|&end| end < elves[i].range.min);
+        best[j + 1] = best[j].max(best[before] + elves[i].range.length());
+    }
+
+    let mut keep = vec![false; elves.len()];
+    let mut j = order.len();
+    while j > 0 {
+        let i = order[j - 1];
+        if best[j] == best[j - 1] {
+            j -= 1;
+        } else {
+            keep[i] = true;
+            j = ends.partition_point(|&end| end < elves[i].range.min);
+        }
+    }
+    Release::new(elves, &keep)
+}
@@ -0,0 +1,46 @@
+// Overlaps between every elf in the input, not just the two on each line.
+
+use std::collections::BTreeSet;
+
+use crate::{elves, Elf};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Overlaps {
+    // Every pair of elves sharing at least one section, the earlier starting first.
+    pub pairs: Vec<(Elf, Elf)>,
+    // The most elves assigned to a single section.
+    pub max_depth: usize,
+    // The lowest section assigned to `max_depth` elves.
+    pub busiest: Option<u32>,
+}
+
+// Sweeps the elves in order of their first section, keeping the ones still active
+// ordered by their last section. Runs in O(n log n + k) for k overlapping pairs.
+pub fn overlaps(input: &str) -> Overlaps {
+    let mut elves = elves(input);
+    elves.sort_by_key(|e| (e.range.min, e.range.max, e.line, e.position));
+
+    let mut active = BTreeSet::new();
+    let mut overlaps = Overlaps {
+        pairs: vec![],
+        max_depth: 0,
+        busiest: None,
+    };
+    for (i, elf) in elves.iter().enumerate() {
+        while let Some(&(max, j)) = active.first() {
+            if max >= elf.range.min {
+                break;
+            }
+            active.remove(&(max, j));
+        }
+        overlaps
+            .pairs
+            .extend(active.iter().map(|&(_, j)| (elves[j], *elf)));
+        active.insert((elf.range.max, i));
+        if active.len() > overlaps.max_depth {
+            overlaps.max_depth = active.len();
+            overlaps.busiest = Some(elf.range.min);
+        }
+    }
+    overlaps
+}
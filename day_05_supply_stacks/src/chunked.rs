@@ -0,0 +1,303 @@
+// Stacks made of shared slices of crates, for inputs with millions of crates. Each
+// stack keeps its slices in a treap ordered from the bottom up, so a move splits
+// and joins two trees in expected logarithmic time in the number of slices, however
+// many crates or slices it carries.
+
+use std::mem;
+use std::rc::Rc;
+
+use crate::generate::XorShift;
+use crate::{Crane, Move, Stacks};
+
+// A view of crates in a shared buffer, from the bottom up. A reversed view runs
+// from the end of its range down to the start.
+#[derive(Clone, Debug)]
+struct Chunk {
+    data: Rc<[String]>,
+    start: usize,
+    end: usize,
+    reversed: bool,
+}
+
+impl Chunk {
+    fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    fn top(&self) -> &String {
+        if self.reversed {
+            &self.data[self.start]
+        } else {
+            &self.data[self.end - 1]
+        }
+    }
+
+    fn bottom(&self) -> &String {
+        if self.reversed {
+            &self.data[self.end - 1]
+        } else {
+            &self.data[self.start]
+        }
+    }
+
+    // Splits off the top `n` crates, leaving the rest in `self`.
+    fn split_top(&mut self, n: usize) -> Chunk {
+        let mut top = self.clone();
+        if self.reversed {
+            top.end = self.start + n;
+            self.start += n;
+        } else {
+            top.start = self.end - n;
+            self.end -= n;
+        }
+        top
+    }
+
+    fn flip(&mut self) {
+        self.reversed = !self.reversed;
+    }
+
+    fn crates(&self) -> Box<dyn Iterator<Item = &String> + '_> {
+        let range = self.data[self.start..self.end].iter();
+        if self.reversed {
+            Box::new(range.rev())
+        } else {
+            Box::new(range)
+        }
+    }
+}
+
+type Tree = Option<Box<Node>>;
+
+// A treap node. `flipped` marks a pending reversal of the whole subtree: its
+// children swap places and every chunk in it is flipped once it is pushed down.
+#[derive(Clone, Debug)]
+struct Node {
+    chunk: Chunk,
+    priority: u64,
+    height: usize,
+    flipped: bool,
+    below: Tree,
+    above: Tree,
+}
+
+fn height(tree: &Tree) -> usize {
+    tree.as_ref().map_or(0, |n| n.height)
+}
+
+fn flip(tree: &mut Tree) {
+    if let Some(node) = tree {
+        node.flipped = !node.flipped;
+    }
+}
+
+impl Node {
+    fn new(chunk: Chunk, priority: u64) -> Box<Node> {
+        Box::new(Node {
+            height: chunk.len(),
+            chunk,
+            priority,
+            flipped: false,
+            below: None,
+            above: None,
+        })
+    }
+
+    fn push_down(&mut self) {
+        if self.flipped {
+            mem::swap(&mut self.below, &mut self.above);
+            self.chunk.flip();
+            flip(&mut self.below);
+            flip(&mut self.above);
+            self.flipped = false;
+        }
+    }
+
+    fn update(&mut self) {
+        self.height = height(&self.below) + self.chunk.len() + height(&self.above);
+    }
+}
+
+// Stacks `above` on top of `below`.
+fn join(below: Tree, above: Tree) -> Tree {
+    match (below, above) {
+        (None, tree) | (tree, None) => tree,
+        (Some(mut b), Some(mut a)) => {
+            if b.priority >= a.priority {
+                b.push_down();
+                b.above = join(b.above.take(), Some(a));
+                b.update();
+                Some(b)
+            } else {
+                a.push_down();
+                a.below = join(Some(b), a.below.take());
+                a.update();
+                Some(a)
+            }
+        }
+    }
+}
+
+// Splits into the bottom `n` crates and the rest. A chunk that straddles the cut
+// is split in two, and its top part gets `priority`.
+fn split(tree: Tree, n: usize, priority: u64) -> (Tree, Tree) {
+    let Some(mut node) = tree else {
+        return (None, None);
+    };
+    node.push_down();
+    let below = height(&node.below);
+    let len = node.chunk.len();
+    if n <= below {
+        let (bottom, top) = split(node.below.take(), n, priority);
+        node.below = top;
+        node.update();
+        (bottom, Some(node))
+    } else if n >= below + len {
+        let (bottom, top) = split(node.above.take(), n - below - len, priority);
+        node.above = bottom;
+        node.update();
+        (Some(node), top)
+    } else {
+        let cut = Node::new(node.chunk.split_top(below + len - n), priority);
+        let above = node.above.take();
+        node.update();
+        (Some(node), join(Some(cut), above))
+    }
+}
+
+// Calls `visit` on the chunks from the bottom up, flipped as they would be once every
+// pending reversal is pushed down.
+fn for_each_chunk(tree: &Tree, flipped: bool, visit: &mut impl FnMut(&Chunk)) {
+    if let Some(node) = tree {
+        let flipped = flipped != node.flipped;
+        let (first, second) = if flipped {
+            (&node.above, &node.below)
+        } else {
+            (&node.below, &node.above)
+        };
+        for_each_chunk(first, flipped, visit);
+        let mut chunk = node.chunk.clone();
+        if flipped {
+            chunk.flip();
+        }
+        visit(&chunk);
+        for_each_chunk(second, flipped, visit);
+    }
+}
+
+fn top(tree: &Tree) -> Option<&String> {
+    let mut node = tree.as_deref()?;
+    let mut flipped = false;
+    loop {
+        flipped = flipped != node.flipped;
+        let next = if flipped { &node.below } else { &node.above };
+        match next.as_deref() {
+            Some(n) => node = n,
+            None if flipped => return Some(node.chunk.bottom()),
+            None => return Some(node.chunk.top()),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ChunkedStacks {
+    labels: Vec<String>,
+    stacks: Vec<Tree>,
+    rng: XorShift,
+}
+
+impl From<&Stacks> for ChunkedStacks {
+    fn from(stacks: &Stacks) -> Self {
+        let mut rng = XorShift::new(2022);
+        Self {
+            labels: stacks.labels().to_vec(),
+            stacks: stacks
+                .iter()
+                .map(|(_, crates)| {
+                    let chunk = Chunk {
+                        data: crates.as_slice().into(),
+                        start: 0,
+                        end: crates.len(),
+                        reversed: false,
+                    };
+                    (chunk.len() > 0).then(|| Node::new(chunk, rng.next_u64()))
+                })
+                .collect(),
+            rng,
+        }
+    }
+}
+
+impl ChunkedStacks {
+    fn position(&self, label: &str) -> Result<usize, String> {
+        self.labels
+            .iter()
+            .position(|l| l == label)
+            .ok_or_else(|| format!("unknown stack {label:?}"))
+    }
+
+    // The top `n` crates of a stack.
+    fn take(&mut self, stack: usize, n: usize) -> Tree {
+        let tree = self.stacks[stack].take();
+        let keep = height(&tree) - n;
+        let (bottom, top) = split(tree, keep, self.rng.next_u64());
+        self.stacks[stack] = bottom;
+        top
+    }
+
+    fn put(&mut self, stack: usize, tree: Tree) {
+        self.stacks[stack] = join(self.stacks[stack].take(), tree);
+    }
+
+    // Carries out one move and returns its cost, like `apply` on `Stacks`.
+    pub fn apply(&mut self, m: &Move, crane: &dyn Crane) -> Result<u64, String> {
+        let (from, to) = (self.position(&m.from)?, self.position(&m.to)?);
+        let has = height(&self.stacks[from]);
+        if m.count > has {
+            return Err(format!(
+                "cannot move {} crates from stack {}, which has {has}",
+                m.count, m.from
+            ));
+        }
+
+        if crane.reverses() {
+            let mut taken = self.take(from, m.count);
+            flip(&mut taken);
+            self.put(to, taken);
+            return Ok(crane.cost(m.count, m.count));
+        }
+
+        let mut remaining = m.count;
+        let mut lifts = 0;
+        while remaining > 0 {
+            let n = crane.lift(remaining).clamp(1, remaining);
+            let taken = self.take(from, n);
+            self.put(to, taken);
+            remaining -= n;
+            lifts += 1;
+        }
+        Ok(crane.cost(m.count, lifts))
+    }
+
+    pub fn heights(&self) -> Vec<usize> {
+        self.stacks.iter().map(height).collect()
+    }
+
+    pub fn top_string(&self) -> String {
+        self.stacks
+            .iter()
+            .map(|s| top(s).map_or(" ", String::as_str))
+            .collect()
+    }
+
+    pub fn to_stacks(&self) -> Stacks {
+        let mut stacks = Stacks::new(self.labels.clone()).unwrap();
+        for (label, stack) in self.labels.iter().zip(&self.stacks) {
+            let crates = stacks.get_mut(label).unwrap();
+            for_each_chunk(stack, false, &mut |chunk| {
+                crates.extend(chunk.crates().cloned())
+            });
+        }
+        stacks
+    }
+}
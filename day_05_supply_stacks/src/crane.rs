@@ -0,0 +1,168 @@
+// Crane models and the simulator that runs moves through them.
+
+use crate::{parse_input, Move, Stacks, SupplyError};
+
+pub trait Crane {
+    fn name(&self) -> String;
+
+    // How many crates the next lift takes when `remaining` are still to be moved.
+    // Each lift takes crates off the top and puts them down in the same order.
+    fn lift(&self, remaining: usize) -> usize;
+
+    // Time taken by one move of `crates` crates in `lifts` lifts.
+    fn cost(&self, _crates: usize, lifts: usize) -> u64 {
+        lifts as u64
+    }
+
+    // Whether every lift takes a single crate, so that a move reverses the crates it
+    // carries and can be done in one step.
+    fn reverses(&self) -> bool {
+        false
+    }
+}
+
+// Moves one crate at a time.
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+
+    fn lift(&self, _remaining: usize) -> usize {
+        1
+    }
+
+    fn reverses(&self) -> bool {
+        true
+    }
+}
+
+// Moves all the crates of a move at once.
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+
+    fn lift(&self, remaining: usize) -> usize {
+        remaining
+    }
+}
+
+// Moves at most `capacity` crates at once, splitting larger moves into lifts.
+pub struct Limited {
+    pub capacity: usize,
+}
+
+impl Crane for Limited {
+    fn name(&self) -> String {
+        format!("limited to {}", self.capacity)
+    }
+
+    fn lift(&self, remaining: usize) -> usize {
+        remaining.min(self.capacity.max(1))
+    }
+
+    fn reverses(&self) -> bool {
+        self.capacity <= 1
+    }
+}
+
+// Another crane's lifts, charged a fixed time per move plus a time per crate.
+pub struct Timed<C> {
+    pub crane: C,
+    pub per_move: u64,
+    pub per_crate: u64,
+}
+
+impl<C: Crane> Crane for Timed<C> {
+    fn name(&self) -> String {
+        format!(
+            "{} ({} per move, {} per crate)",
+            self.crane.name(),
+            self.per_move,
+            self.per_crate
+        )
+    }
+
+    fn lift(&self, remaining: usize) -> usize {
+        self.crane.lift(remaining)
+    }
+
+    fn cost(&self, crates: usize, _lifts: usize) -> u64 {
+        self.per_move + self.per_crate * crates as u64
+    }
+
+    fn reverses(&self) -> bool {
+        self.crane.reverses()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Simulation {
+    pub tops: String,
+    pub cost: u64,
+    // Number of crates on each stack, from the first stack.
+    pub heights: Vec<usize>,
+}
+
+pub fn simulate(input: &str, crane: &dyn Crane) -> Result<Simulation, SupplyError> {
+    let (mut stacks, moves) = parse_input(input)?;
+    let mut cost = 0;
+    for (line, m) in &moves {
+        cost += apply(&mut stacks, m, crane).map_err(|message| SupplyError::Move {
+            line: *line,
+            message,
+        })?;
+    }
+
+    Ok(Simulation {
+        tops: stacks.top_string(),
+        cost,
+        heights: stacks.heights(),
+    })
+}
+
+// Carries out one move and returns its cost. Leaves the stacks alone if the move
+// names an unknown stack or takes more crates than there are.
+pub fn apply(stacks: &mut Stacks, m: &Move, crane: &dyn Crane) -> Result<u64, String> {
+    let has = stacks
+        .get(&m.from)
+        .ok_or_else(|| format!("unknown stack {:?}", m.from))?
+        .len();
+    if stacks.get(&m.to).is_none() {
+        return Err(format!("unknown stack {:?}", m.to));
+    }
+    if m.count > has {
+        return Err(format!(
+            "cannot move {} crates from stack {}, which has {has}",
+            m.count, m.from
+        ));
+    }
+
+    if crane.reverses() {
+        let from = stacks.get_mut(&m.from).unwrap();
+        let mut crates = from.split_off(from.len() - m.count);
+        crates.reverse();
+        stacks.get_mut(&m.to).unwrap().extend(crates);
+        return Ok(crane.cost(m.count, m.count));
+    }
+
+    let mut remaining = m.count;
+    let mut lifts = 0;
+    while remaining > 0 {
+        let n = crane.lift(remaining).clamp(1, remaining);
+        lift(stacks, &m.from, &m.to, n);
+        remaining -= n;
+        lifts += 1;
+    }
+    Ok(crane.cost(m.count, lifts))
+}
+
+fn lift(stacks: &mut Stacks, from: &str, to: &str, n: usize) {
+    let from = stacks.get_mut(from).unwrap();
+    let crates = from.split_off(from.len() - n);
+    stacks.get_mut(to).unwrap().extend(crates);
+}
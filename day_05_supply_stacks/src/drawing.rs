@@ -0,0 +1,61 @@
+// Writes stacks back out in the puzzle's drawing format.
+
+use crate::crane::apply;
+use crate::{parse_input, Crane, Stacks, SupplyError};
+
+// The drawing of `stacks`, crate rows from the top and the number row last, each
+// line padded to the full width as in the puzzle input. Columns widen to fit long
+// stack or crate labels.
+pub fn draw(stacks: &Stacks) -> String {
+    let widths = stacks
+        .iter()
+        .map(|(label, crates)| {
+            let widest = crates
+                .iter()
+                .map(|c| c.chars().count() + 2)
+                .max()
+                .unwrap_or(3);
+            widest.max(label.chars().count())
+        })
+        .collect::<Vec<_>>();
+    let height = stacks.heights().into_iter().max().unwrap_or(0);
+
+    let mut lines = (0..height)
+        .rev()
+        .map(|row| {
+            stacks
+                .iter()
+                .zip(&widths)
+                .map(|((_, crates), &w)| match crates.get(row) {
+                    Some(c) => format!("{:^w$}", format!("[{c}]")),
+                    None => " ".repeat(w),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>();
+    lines.push(
+        stacks
+            .labels()
+            .iter()
+            .zip(&widths)
+            .map(|(label, &w)| format!("{label:^w$}"))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    lines.join("\n") + "\n"
+}
+
+// The drawing before any move and after each move.
+pub fn frames(input: &str, crane: &dyn Crane) -> Result<Vec<String>, SupplyError> {
+    let (mut stacks, moves) = parse_input(input)?;
+    let mut frames = vec![draw(&stacks)];
+    for (line, m) in &moves {
+        apply(&mut stacks, m, crane).map_err(|message| SupplyError::Move {
+            line: *line,
+            message,
+        })?;
+        frames.push(draw(&stacks));
+    }
+    Ok(frames)
+}
@@ -0,0 +1,18 @@
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SupplyError {
+    Drawing { line: usize, message: String },
+    Move { line: usize, message: String },
+}
+
+impl fmt::Display for SupplyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SupplyError::Drawing { line, message } => write!(f, "line {line}: {message}"),
+            SupplyError::Move { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for SupplyError {}
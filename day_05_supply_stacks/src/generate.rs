@@ -0,0 +1,86 @@
+// Random drawings and moves for exercising the simulators at scale.
+
+use crate::{Move, Stacks};
+
+#[derive(Clone, Debug)]
+pub struct XorShift(u64);
+
+impl XorShift {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+}
+
+// `stacks` stacks holding `crates` crates between them, and `moves` valid moves
+// of up to `max_count` crates each. Moves need two stacks to move between.
+pub fn generate(
+    stacks: usize,
+    crates: usize,
+    moves: usize,
+    max_count: usize,
+    seed: u64,
+) -> Result<(Stacks, Vec<Move>), String> {
+    if stacks < 2 {
+        return Err(format!("cannot generate moves between {stacks} stacks"));
+    }
+    let mut rng = XorShift::new(seed);
+    let labels = (1..=stacks).map(|i| i.to_string()).collect::<Vec<_>>();
+    let mut start = Stacks::new(labels.clone()).unwrap();
+    for i in 0..crates {
+        let label = &labels[rng.below(stacks as u64) as usize];
+        start
+            .get_mut(label)
+            .unwrap()
+            .push(((b'A' + (i % 26) as u8) as char).to_string());
+    }
+
+    let moves = random_moves(&start, moves, max_count, rng.next_u64())?;
+    Ok((start, moves))
+}
+
+// `moves` valid moves of up to `max_count` crates each, starting from `stacks`.
+pub fn random_moves(
+    stacks: &Stacks,
+    moves: usize,
+    max_count: usize,
+    seed: u64,
+) -> Result<Vec<Move>, String> {
+    let labels = stacks.labels();
+    let n = labels.len();
+    if n < 2 {
+        return Err(format!("cannot generate moves between {n} stacks"));
+    }
+    let mut rng = XorShift::new(seed);
+    let mut heights = stacks.heights();
+    let empty = heights.iter().all(|&h| h == 0);
+    Ok((0..moves)
+        .map(|_| {
+            let from = loop {
+                let i = rng.below(n as u64) as usize;
+                if heights[i] > 0 || empty {
+                    break i;
+                }
+            };
+            let to = (from + 1 + rng.below(n as u64 - 1) as usize) % n;
+            let count = rng.below(heights[from].min(max_count) as u64 + 1) as usize;
+            heights[from] -= count;
+            heights[to] += count;
+            Move {
+                count,
+                from: labels[from].clone(),
+                to: labels[to].clone(),
+            }
+        })
+        .collect())
+}
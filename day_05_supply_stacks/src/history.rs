@@ -0,0 +1,252 @@
+// Records the moves made on the stacks so that earlier states can be looked at
+// again. Each move is kept as the move and the sizes of its lifts, with a full copy
+// of the stacks only every CHECKPOINT moves.
+
+use crate::{apply, parse_input, Crane, Move, Stacks, SupplyError};
+
+const CHECKPOINT: usize = 64;
+
+// Where a crate is: its stack and its height from the bottom, from 0.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub stack: String,
+    pub height: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Step {
+    m: Move,
+    // Lift sizes as runs of (size, repeats), from the first lift.
+    lifts: Vec<(usize, usize)>,
+}
+
+impl Step {
+    fn new(m: Move, crane: &dyn Crane) -> Self {
+        let mut lifts: Vec<(usize, usize)> = vec![];
+        if crane.reverses() {
+            lifts.push((1, m.count));
+        } else {
+            let mut remaining = m.count;
+            while remaining > 0 {
+                let n = crane.lift(remaining).clamp(1, remaining);
+                match lifts.last_mut() {
+                    Some((size, repeats)) if *size == n => *repeats += 1,
+                    _ => lifts.push((n, 1)),
+                }
+                remaining -= n;
+            }
+        }
+        Self { m, lifts }
+    }
+
+    fn redo(&self, stacks: &mut Stacks) {
+        let from = stacks.get_mut(&self.m.from).unwrap();
+        let carried = from.split_off(from.len() - self.m.count);
+        let to = stacks.get_mut(&self.m.to).unwrap();
+        let mut end = carried.len();
+        for &(size, repeats) in &self.lifts {
+            for _ in 0..repeats {
+                to.extend_from_slice(&carried[end - size..end]);
+                end -= size;
+            }
+        }
+    }
+
+    fn undo(&self, stacks: &mut Stacks) {
+        let to = stacks.get_mut(&self.m.to).unwrap();
+        let landed = to.split_off(to.len() - self.m.count);
+        let mut blocks = vec![];
+        let mut start = 0;
+        for &(size, repeats) in &self.lifts {
+            for _ in 0..repeats {
+                blocks.push(&landed[start..start + size]);
+                start += size;
+            }
+        }
+        let from = stacks.get_mut(&self.m.from).unwrap();
+        for block in blocks.into_iter().rev() {
+            from.extend_from_slice(block);
+        }
+    }
+
+    // Where the crate at `height` of the `from` stack ends up, given the heights of
+    // the `from` and `to` stacks before the move.
+    fn carry(&self, height: usize, from_height: usize, to_height: usize) -> usize {
+        // Crates above it in the carried block, which were lifted before it.
+        let mut depth = from_height - 1 - height;
+        let mut landed = 0;
+        for &(size, repeats) in &self.lifts {
+            if depth < size * repeats {
+                let (block, offset) = (depth / size, depth % size);
+                return to_height + landed + block * size + size - 1 - offset;
+            }
+            depth -= size * repeats;
+            landed += size * repeats;
+        }
+        unreachable!("crate is not in the carried block")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct History {
+    steps: Vec<Step>,
+    // checkpoints[i] is the state after i * CHECKPOINT moves.
+    checkpoints: Vec<Stacks>,
+    current: Stacks,
+    // Moves applied to reach `current`; the rest can be redone.
+    position: usize,
+}
+
+impl History {
+    pub fn new(start: Stacks) -> Self {
+        Self {
+            steps: vec![],
+            checkpoints: vec![start.clone()],
+            current: start,
+            position: 0,
+        }
+    }
+
+    pub fn from_input(input: &str, crane: &dyn Crane) -> Result<Self, SupplyError> {
+        let (stacks, moves) = parse_input(input)?;
+        let mut history = History::new(stacks);
+        for (line, m) in moves {
+            history
+                .push(m, crane)
+                .map_err(|message| SupplyError::Move { line, message })?;
+        }
+        Ok(history)
+    }
+
+    // Makes a move from the current state and returns its cost. Moves that were
+    // undone are forgotten.
+    pub fn push(&mut self, m: Move, crane: &dyn Crane) -> Result<u64, String> {
+        let cost = apply(&mut self.current, &m, crane)?;
+        self.steps.truncate(self.position);
+        self.checkpoints.truncate(self.position / CHECKPOINT + 1);
+        self.steps.push(Step::new(m, crane));
+        self.position += 1;
+        if self.position.is_multiple_of(CHECKPOINT) {
+            self.checkpoints.push(self.current.clone());
+        }
+        Ok(cost)
+    }
+
+    // Number of recorded moves, including undone ones.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn current(&self) -> &Stacks {
+        &self.current
+    }
+
+    pub fn undo(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        self.position -= 1;
+        self.steps[self.position].undo(&mut self.current);
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        if self.position == self.steps.len() {
+            return false;
+        }
+        self.steps[self.position].redo(&mut self.current);
+        self.position += 1;
+        true
+    }
+
+    // The stacks after the first `k` moves.
+    pub fn state_at(&self, k: usize) -> Option<Stacks> {
+        if k > self.steps.len() {
+            return None;
+        }
+        let mut stacks = self.checkpoints[k / CHECKPOINT].clone();
+        for step in &self.steps[k / CHECKPOINT * CHECKPOINT..k] {
+            step.redo(&mut stacks);
+        }
+        Some(stacks)
+    }
+
+    pub fn top_string_at(&self, k: usize) -> Option<String> {
+        Some(self.state_at(k)?.top_string())
+    }
+
+    // Follows the crate that started at `start` through the moves, calling `visit`
+    // with its location and the stack heights after every move, from move 0, until
+    // `visit` returns true.
+    fn track(
+        &self,
+        start: &Location,
+        moves: usize,
+        mut visit: impl FnMut(usize, &Location, &[usize]) -> bool,
+    ) -> Option<Location> {
+        let stacks = &self.checkpoints[0];
+        if start.height >= stacks.get(&start.stack)?.len() {
+            return None;
+        }
+        let mut heights = stacks.heights();
+        let mut at = start.clone();
+        let mut stack = stacks.position(&at.stack).unwrap();
+        for k in 0..=moves {
+            if visit(k, &at, &heights) {
+                break;
+            }
+            let Some(step) = self.steps.get(k).filter(|_| k < moves) else {
+                break;
+            };
+            let from = stacks.position(&step.m.from).unwrap();
+            let to = stacks.position(&step.m.to).unwrap();
+            if stack == from && at.height + step.m.count >= heights[from] {
+                // A move onto its own stack lands where the lifted crates were.
+                let base = if from == to {
+                    heights[to] - step.m.count
+                } else {
+                    heights[to]
+                };
+                at = Location {
+                    stack: step.m.to.clone(),
+                    height: step.carry(at.height, heights[from], base),
+                };
+                stack = to;
+            }
+            heights[from] -= step.m.count;
+            heights[to] += step.m.count;
+        }
+        Some(at)
+    }
+
+    // Where the crate that started at `start` is after the first `k` moves.
+    pub fn locate(&self, start: &Location, k: usize) -> Option<Location> {
+        if k > self.steps.len() {
+            return None;
+        }
+        self.track(start, k, |_, _, _| false)
+    }
+
+    // The first number of moves after which the crate that started at `start` is
+    // on top of its stack.
+    pub fn first_on_top(&self, start: &Location) -> Option<usize> {
+        let stacks = &self.checkpoints[0];
+        let mut found = None;
+        self.track(start, self.steps.len(), |k, at, heights| {
+            let on_top = heights[stacks.position(&at.stack).unwrap()] == at.height + 1;
+            if on_top {
+                found = Some(k);
+            }
+            on_top
+        })?;
+        found
+    }
+}
@@ -1,118 +1,408 @@
-use std::collections::HashMap;
+use std::fmt;
 
-type Stacks = HashMap<usize, Vec<char>>;
-type Moves = Vec<Move>;
+mod chunked;
+mod crane;
+mod drawing;
+mod error;
+mod generate;
+mod history;
+mod parse;
+mod plan;
+mod stacks;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-struct Move {
-    count: usize,
-    from: usize,
-    to: usize,
+pub use crate::chunked::ChunkedStacks;
+pub use crate::crane::{
+    apply, simulate, Crane, CrateMover9000, CrateMover9001, Limited, Simulation, Timed,
+};
+pub use crate::drawing::{draw, frames};
+pub use crate::error::SupplyError;
+pub use crate::generate::{generate, random_moves};
+pub use crate::history::{History, Location};
+pub use crate::parse::parse_input;
+pub use crate::plan::{format_plan, plan, Target};
+pub use crate::stacks::Stacks;
+
+// Moves with the input line they were read from.
+pub type Moves = Vec<(usize, Move)>;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub count: usize,
+    pub from: String,
+    pub to: String,
 }
 
-fn line_to_crates(line: &str) -> Vec<char> {
-    let mut v: Vec<char> = Vec::new();
-    for i in (1..).step_by(4) {
-        if i > line.len() {
-            break;
-        }
-        v.push(line.as_bytes()[i] as char)
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
     }
-    v
 }
 
-fn insert_crate_to_stack(mut m: Stacks, line: &str) -> Stacks {
-    let crates = line_to_crates(line);
-    for (i, c) in crates.iter().enumerate() {
-        let v = m.entry(i + 1).or_default();
-        if *c != ' ' {
-            v.push(*c);
-        }
-    }
-    m
+pub fn part_one(input: &str) -> String {
+    simulate(input, &CrateMover9000)
+        .unwrap_or_else(|e| panic!("{e}"))
+        .tops
 }
 
-fn parse_move(line: &str) -> Move {
-    let vs = line
-        .split_ascii_whitespace()
-        .filter_map(|s| {
-            match s.parse::<usize>() {
-                Ok(num) => Some(num),
-                Err(_) => None,
-            }
-        })
-        .collect::<Vec<_>>();
-    Move { count: vs[0], from: vs[1], to: vs[2], }
+pub fn part_two(input: &str) -> String {
+    simulate(input, &CrateMover9001)
+        .unwrap_or_else(|e| panic!("{e}"))
+        .tops
 }
 
-fn stack_top_string(stacks: &Stacks) -> String {
-    let mut tops = Vec::new();
-    for i in 1..=stacks.len() {
-        let v = stacks.get(&i).unwrap();
-        tops.push(*v.last().unwrap());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../data/example.txt");
+
+    #[test]
+    fn it_works() {
+        assert_eq!(part_one(EXAMPLE), "CMZ".to_string());
+        assert_eq!(part_two(EXAMPLE), "MCD".to_string());
     }
-    String::from_iter(tops)
-}
 
-fn parse_input(input: &str) -> (Stacks, Moves) {
-    let (stacks, moves) = input
-        .split_once("\n\n")
-        .unwrap();
-
-    let stacks = stacks
-        .lines()
-        .rev()
-        .skip(1)
-        .fold(HashMap::new(), |m, line| {
-            insert_crate_to_stack(m, line)
-        });
-
-    let moves = moves
-        .lines()
-        .map(parse_move)
-        .collect::<Vec<_>>();
-
-    (stacks, moves)
-}
+    #[test]
+    fn cranes_work() {
+        let s = simulate(EXAMPLE, &CrateMover9000).unwrap();
+        assert_eq!(
+            s,
+            Simulation {
+                tops: "CMZ".to_string(),
+                cost: 7,
+                heights: vec![1, 1, 4]
+            }
+        );
+        assert_eq!(simulate(EXAMPLE, &CrateMover9001).unwrap().cost, 4);
 
-pub fn part_one(input: &str) -> String {
-    let (mut stacks, moves) = parse_input(input);
+        // Lifts of two move the three crates as [N D] then [Z] on top of P.
+        let s = simulate(EXAMPLE, &Limited { capacity: 2 }).unwrap();
+        assert_eq!(s.tops, "MCZ");
+        assert_eq!(s.cost, 5);
+        assert_eq!(
+            simulate(EXAMPLE, &Limited { capacity: 3 }).unwrap().tops,
+            "MCD"
+        );
+
+        let timed = Timed {
+            crane: CrateMover9001,
+            per_move: 10,
+            per_crate: 2,
+        };
+        assert_eq!(
+            simulate(EXAMPLE, &timed).unwrap(),
+            Simulation {
+                tops: "MCD".to_string(),
+                cost: 54,
+                heights: vec![1, 1, 4]
+            }
+        );
+    }
+
+    #[test]
+    fn drawing_works() {
+        let (stacks, _) = parse_input(EXAMPLE).unwrap();
+        let drawing = draw(&stacks);
+        assert!(EXAMPLE.starts_with(&drawing));
+        assert_eq!(parse_input(&drawing).unwrap().0, stacks);
 
-    for m in moves.iter() {
-        for _ in 0..m.count {
-            let char = stacks.get_mut(&m.from).unwrap().pop().unwrap();
-            stacks.get_mut(&m.to).unwrap().push(char);
+        let frames = frames(EXAMPLE, &CrateMover9000).unwrap();
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[0], draw(&stacks));
+        assert_eq!(
+            frames[2],
+            "        [Z]\n        [N]\n    [C] [D]\n    [M] [P]\n 1   2   3 \n"
+        );
+        assert_eq!(parse_input(&frames[2]).unwrap().0.top_string(), " CZ");
+        for frame in &frames {
+            let (stacks, _) = parse_input(frame).unwrap();
+            assert_eq!(&draw(&stacks), frame);
         }
     }
 
-    stack_top_string(&stacks)
-}
+    #[test]
+    fn parser_works() {
+        let wide = "[AB]         [X]\n[C]  [DEF]   [Y]\n 1    2     10 \n\nmove 1 from 10 to 2\nmove 2 from 1 to 2\n";
+        let (stacks, moves) = parse_input(wide).unwrap();
+        assert_eq!(stacks.labels(), &["1", "2", "10"]);
+        assert_eq!(
+            stacks.get("1"),
+            Some(&vec!["C".to_string(), "AB".to_string()])
+        );
+        assert_eq!(stacks.get("10").unwrap().len(), 2);
+        assert_eq!(
+            moves[0],
+            (
+                5,
+                Move {
+                    count: 1,
+                    from: "10".to_string(),
+                    to: "2".to_string()
+                }
+            )
+        );
+        assert_eq!(simulate(wide, &CrateMover9001).unwrap().tops, " ABY");
+        assert_eq!(parse_input(&draw(&stacks)).unwrap().0, stacks);
 
-pub fn part_two(input: &str) -> String {
-    let (mut stacks, moves) = parse_input(input);
+        let stacks = (1..=12)
+            .map(|i| format!("[{}]", (b'a' + i as u8) as char))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let numbers = (1..=12)
+            .map(|i| format!("{i:^3}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let input = format!("{stacks}\n{numbers}\n\nmove 1 from 11 to 12\n");
+        assert_eq!(part_two(&input), "bcdefghijk l");
 
-    for m in moves.iter() {
-        let mut crates = Vec::new();
-        for _ in 0..m.count {
-            crates.push(stacks.get_mut(&m.from).unwrap().pop().unwrap());
-        }
-        for _ in 0..m.count {
-            stacks.get_mut(&m.to).unwrap().push(crates.pop().unwrap());
+        let error = |input: &str| simulate(input, &CrateMover9000).unwrap_err();
+        let (drawing, _) = EXAMPLE.split_once("\n\n").unwrap();
+        assert_eq!(
+            error(&format!("{drawing}\n\nmove 1 from 2 to 4\n")),
+            SupplyError::Move {
+                line: 6,
+                message: "unknown stack \"4\"".to_string()
+            }
+        );
+        assert_eq!(
+            error(&format!(
+                "{drawing}\n\nmove 1 from 2 to 1\nmove 4 from 2 to 3\n"
+            )),
+            SupplyError::Move {
+                line: 7,
+                message: "cannot move 4 crates from stack 2, which has 2".to_string()
+            }
+        );
+        assert_eq!(
+            error(&format!("{drawing}\n\nmove one from 2 to 1\n")),
+            SupplyError::Move {
+                line: 6,
+                message: "invalid crate count \"one\"".to_string()
+            }
+        );
+        assert_eq!(
+            error("[A]\n    [B]\n 1   2\n"),
+            SupplyError::Drawing {
+                line: 1,
+                message: "crate [A] floats above stack 1".to_string()
+            }
+        );
+        assert_eq!(
+            error("        [A]\n 1   2\n"),
+            SupplyError::Drawing {
+                line: 1,
+                message: "crate [A] at column 9 is not above any stack".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn planner_works() {
+        let (stacks, _) = parse_input(EXAMPLE).unwrap();
+        assert_eq!(
+            plan(&stacks, &Target::tops("NDP"), &CrateMover9000, 3),
+            Some(vec![])
+        );
+
+        let moves = plan(&stacks, &Target::tops("CMZ"), &CrateMover9000, 4).unwrap();
+        assert_eq!(moves.len(), 2);
+        let replay = format!("{}\n{}", draw(&stacks), format_plan(&moves));
+        assert_eq!(part_one(&replay), "CMZ");
+
+        let (goal, _) = parse_input(&frames(EXAMPLE, &CrateMover9001).unwrap()[4]).unwrap();
+        let moves = plan(&stacks, &Target::Layout(goal.clone()), &CrateMover9001, 4).unwrap();
+        assert!(moves.len() <= 4);
+        let mut replay = stacks.clone();
+        for m in &moves {
+            apply(&mut replay, m, &CrateMover9001).unwrap();
         }
+        assert_eq!(replay, goal);
+
+        assert_eq!(
+            plan(&stacks, &Target::tops("QQQ"), &CrateMover9000, 8),
+            None
+        );
+        assert_eq!(
+            plan(&stacks, &Target::tops("ZZ "), &CrateMover9000, 8),
+            None
+        );
+        assert_eq!(
+            plan(&stacks, &Target::tops("N  "), &CrateMover9000, 8).map(|p| p.len()),
+            Some(4)
+        );
+        assert_eq!(
+            plan(&stacks, &Target::tops("MCD"), &CrateMover9001, 1),
+            None
+        );
+        assert_eq!(format_plan(&moves[..1]).lines().count(), 1);
+        assert!(format_plan(&moves).starts_with("move "));
     }
 
-    stack_top_string(&stacks)
-}
+    #[test]
+    fn chunked_works() {
+        let (stacks, moves) = parse_input(EXAMPLE).unwrap();
+        let cranes: [&dyn Crane; 4] = [
+            &CrateMover9000,
+            &CrateMover9001,
+            &Limited { capacity: 2 },
+            &Limited { capacity: 1 },
+        ];
+        for crane in cranes {
+            let mut chunked = ChunkedStacks::from(&stacks);
+            let mut cost = 0;
+            for (_, m) in &moves {
+                cost += chunked.apply(m, crane).unwrap();
+            }
+            let s = simulate(EXAMPLE, crane).unwrap();
+            assert_eq!(
+                (chunked.top_string(), cost, chunked.heights()),
+                (s.tops, s.cost, s.heights)
+            );
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        assert!(generate(1, 10, 10, 10, 7).is_err());
+        assert!(generate(0, 0, 0, 0, 7).is_err());
+        let (start, moves) = generate(2, 0, 5, 10, 7).unwrap();
+        assert_eq!((start.heights(), moves.len()), (vec![0, 0], 5));
+        let (start, moves) = generate(5, 2000, 500, 300, 7).unwrap();
+        for crane in [
+            &CrateMover9000 as &dyn Crane,
+            &CrateMover9001,
+            &Limited { capacity: 7 },
+        ] {
+            let mut plain = start.clone();
+            let mut chunked = ChunkedStacks::from(&start);
+            for m in &moves {
+                assert_eq!(apply(&mut plain, m, crane), chunked.apply(m, crane));
+            }
+            assert_eq!(chunked.to_stacks(), plain);
+        }
 
-    const EXAMPLE: &str = include_str!("../data/example.txt");
+        // Single-crate moves scatter the stacks into small slices before bulk moves.
+        let (start, _) = generate(4, 3000, 0, 0, 5).unwrap();
+        let mut moves = random_moves(&start, 2000, 1, 6).unwrap();
+        let mut after = start.clone();
+        for m in &moves {
+            apply(&mut after, m, &CrateMover9001).unwrap();
+        }
+        moves.extend(random_moves(&after, 200, 3000, 7).unwrap());
+        for crane in [
+            &CrateMover9000 as &dyn Crane,
+            &CrateMover9001,
+            &Limited { capacity: 7 },
+        ] {
+            let mut plain = start.clone();
+            let mut chunked = ChunkedStacks::from(&start);
+            for m in &moves {
+                assert_eq!(apply(&mut plain, m, crane), chunked.apply(m, crane));
+                assert_eq!(chunked.top_string(), plain.top_string());
+            }
+            assert_eq!(chunked.to_stacks(), plain);
+        }
+
+        let mut chunked = ChunkedStacks::from(&stacks);
+        let m = Move {
+            count: 4,
+            from: "2".to_string(),
+            to: "1".to_string(),
+        };
+        assert_eq!(
+            chunked.apply(&m, &CrateMover9001),
+            Err("cannot move 4 crates from stack 2, which has 3".to_string())
+        );
+        assert_eq!(chunked.to_stacks(), stacks);
+    }
 
     #[test]
-    fn it_works() {
-        assert_eq!(part_one(EXAMPLE), "CMZ".to_string());
-        assert_eq!(part_two(EXAMPLE), "MCD".to_string());
+    fn history_works() {
+        let location = |stack: &str, height| Location {
+            stack: stack.to_string(),
+            height,
+        };
+        for crane in [&CrateMover9000 as &dyn Crane, &CrateMover9001] {
+            let mut history = History::from_input(EXAMPLE, crane).unwrap();
+            let frames = frames(EXAMPLE, crane).unwrap();
+            assert_eq!(history.len(), 4);
+            for (k, frame) in frames.iter().enumerate() {
+                assert_eq!(draw(&history.state_at(k).unwrap()), *frame);
+            }
+            assert_eq!(history.state_at(5), None);
+
+            assert!(history.undo() && history.undo());
+            assert_eq!(draw(history.current()), frames[2]);
+            assert!(history.redo());
+            assert_eq!(draw(history.current()), frames[3]);
+            history
+                .push(
+                    Move {
+                        count: 1,
+                        from: "3".to_string(),
+                        to: "1".to_string(),
+                    },
+                    crane,
+                )
+                .unwrap();
+            assert_eq!((history.len(), history.position()), (4, 4));
+            assert!(!history.redo());
+            while history.undo() {}
+            assert_eq!(draw(history.current()), frames[0]);
+        }
+
+        let history = History::from_input(EXAMPLE, &CrateMover9000).unwrap();
+        assert_eq!(history.top_string_at(1), Some("DCP".to_string()));
+        // Z starts at the bottom of stack 1 and reaches the top of stack 3 in move 2.
+        assert_eq!(history.locate(&location("1", 0), 4), Some(location("3", 3)));
+        assert_eq!(history.locate(&location("1", 0), 1), Some(location("1", 0)));
+        assert_eq!(history.first_on_top(&location("1", 0)), Some(2));
+        assert_eq!(history.first_on_top(&location("3", 0)), Some(0));
+        assert_eq!(history.first_on_top(&location("2", 0)), Some(3));
+        assert_eq!(history.locate(&location("3", 1), 0), None);
+
+        // Moving crates onto their own stack keeps them within it.
+        for crane in [&CrateMover9000 as &dyn Crane, &CrateMover9001] {
+            let mut history = History::from_input(EXAMPLE, crane).unwrap();
+            while history.undo() {}
+            history
+                .push(
+                    Move {
+                        count: 2,
+                        from: "2".to_string(),
+                        to: "2".to_string(),
+                    },
+                    crane,
+                )
+                .unwrap();
+            let after = history.current().get("2").unwrap();
+            for height in 0..3 {
+                let at = history.locate(&location("2", height), 1).unwrap();
+                assert_eq!(at.stack, "2");
+                assert_eq!(
+                    after[at.height],
+                    history.state_at(0).unwrap().get("2").unwrap()[height]
+                );
+            }
+        }
+
+        let (start, moves) = generate(4, 500, 300, 40, 11).unwrap();
+        for crane in [
+            &CrateMover9000 as &dyn Crane,
+            &CrateMover9001,
+            &Limited { capacity: 3 },
+        ] {
+            let mut history = History::new(start.clone());
+            let mut plain = start.clone();
+            for m in &moves {
+                history.push(m.clone(), crane).unwrap();
+            }
+            for (k, m) in moves.iter().enumerate() {
+                assert_eq!(history.state_at(k).as_ref(), Some(&plain));
+                let at = history.locate(&location("2", 5), k).unwrap();
+                let crate_ = &start.get("2").unwrap()[5];
+                assert_eq!(&plain.get(&at.stack).unwrap()[at.height], crate_);
+                apply(&mut plain, m, crane).unwrap();
+            }
+            while history.undo() {}
+            assert_eq!(history.current(), &start);
+        }
     }
 }
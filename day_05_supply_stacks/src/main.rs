@@ -1,8 +1,167 @@
-use day_05_supply_stacks::{part_one, part_two};
+use std::time::Instant;
+
+use day_05_supply_stacks::{
+    apply, draw, format_plan, frames, generate, parse_input, part_one, part_two, plan,
+    random_moves, simulate, ChunkedStacks, Crane, CrateMover9000, CrateMover9001, History, Limited,
+    Move, Stacks, Target, Timed,
+};
 
 const INPUT: &str = include_str!("../data/input.txt");
 
+// The original simulation loop, one crate at a time.
+fn crate_by_crate(stacks: &mut Stacks, m: &Move, bulk: bool) {
+    let mut crates = Vec::new();
+    for _ in 0..m.count {
+        crates.push(stacks.get_mut(&m.from).unwrap().pop().unwrap());
+    }
+    if bulk {
+        crates.reverse();
+    }
+    for c in crates {
+        stacks.get_mut(&m.to).unwrap().push(c);
+    }
+}
+
+// Times `moves` on every simulator after making the untimed `setup` moves.
+fn bench_moves(name: &str, start: &Stacks, setup: &[Move], moves: &[Move]) {
+    for (crane, bulk) in [
+        (&CrateMover9000 as &dyn Crane, false),
+        (&CrateMover9001, true),
+    ] {
+        let mut stacks = start.clone();
+        for m in setup {
+            crate_by_crate(&mut stacks, m, bulk);
+        }
+        let t = Instant::now();
+        for m in moves {
+            crate_by_crate(&mut stacks, m, bulk);
+        }
+        println!("{name} {}: crate by crate {:?}", crane.name(), t.elapsed());
+        let expected = stacks.top_string();
+
+        let mut stacks = start.clone();
+        for m in setup {
+            apply(&mut stacks, m, crane).unwrap();
+        }
+        let t = Instant::now();
+        for m in moves {
+            apply(&mut stacks, m, crane).unwrap();
+        }
+        println!("{name} {}: slices {:?}", crane.name(), t.elapsed());
+        assert_eq!(stacks.top_string(), expected);
+
+        let mut stacks = ChunkedStacks::from(start);
+        for m in setup {
+            stacks.apply(m, crane).unwrap();
+        }
+        let t = Instant::now();
+        for m in moves {
+            stacks.apply(m, crane).unwrap();
+        }
+        println!("{name} {}: chunks {:?}", crane.name(), t.elapsed());
+        assert_eq!(stacks.top_string(), expected);
+    }
+}
+
+fn bench(crates: usize, moves: usize) {
+    let (start, bulk) = generate(9, crates, moves, crates, 2022).unwrap();
+    bench_moves("bulk", &start, &[], &bulk);
+
+    // Single-crate moves first break the stacks into many small slices.
+    let setup = random_moves(&start, crates / 5, 1, 2023).unwrap();
+    let mut after = start.clone();
+    for m in &setup {
+        apply(&mut after, m, &CrateMover9001).unwrap();
+    }
+    let bulk = random_moves(&after, moves / 5, crates, 2024).unwrap();
+    bench_moves("fragmented", &start, &setup, &bulk);
+}
+
 fn main() {
-	println!("part one answer: {}", part_one(INPUT));
-	println!("part two answer: {}", part_two(INPUT));
+    if std::env::args().nth(1).as_deref() == Some("bench") {
+        let crates = std::env::args()
+            .nth(2)
+            .map(|s| s.parse().unwrap())
+            .unwrap_or(1_000_000);
+        let moves = std::env::args()
+            .nth(3)
+            .map(|s| s.parse().unwrap())
+            .unwrap_or(2_000);
+        bench(crates, moves);
+        return;
+    }
+
+    if std::env::args().nth(1).as_deref() == Some("cranes") {
+        let cranes: Vec<Box<dyn Crane>> = vec![
+            Box::new(CrateMover9000),
+            Box::new(CrateMover9001),
+            Box::new(Limited { capacity: 3 }),
+            Box::new(Timed {
+                crane: CrateMover9001,
+                per_move: 10,
+                per_crate: 1,
+            }),
+        ];
+        for crane in &cranes {
+            let s = simulate(INPUT, crane.as_ref()).unwrap_or_else(|e| panic!("{e}"));
+            println!(
+                "{}: tops {}, cost {}, heights {:?}",
+                crane.name(),
+                s.tops,
+                s.cost,
+                s.heights
+            );
+        }
+        return;
+    }
+
+    if std::env::args().nth(1).as_deref() == Some("frames") {
+        let crane: &dyn Crane = match std::env::args().nth(2).as_deref() {
+            Some("9000") => &CrateMover9000,
+            _ => &CrateMover9001,
+        };
+        let moves = INPUT.split_once("\n\n").unwrap().1.lines();
+        let frames = frames(INPUT, crane).unwrap_or_else(|e| panic!("{e}"));
+        for (i, (frame, m)) in frames
+            .iter()
+            .zip(std::iter::once("start").chain(moves))
+            .enumerate()
+        {
+            println!("{i}: {m}");
+            println!("{frame}");
+        }
+        return;
+    }
+
+    if std::env::args().nth(1).as_deref() == Some("plan") {
+        let tops = std::env::args()
+            .nth(2)
+            .expect("usage: plan TOPS [9000|9001]");
+        let crane: &dyn Crane = match std::env::args().nth(3).as_deref() {
+            Some("9000") => &CrateMover9000,
+            _ => &CrateMover9001,
+        };
+        let (stacks, _) = parse_input(INPUT).unwrap_or_else(|e| panic!("{e}"));
+        match plan(&stacks, &Target::tops(&tops), crane, 4) {
+            Some(moves) => print!("{}", format_plan(&moves)),
+            None => println!("no plan of at most 4 moves"),
+        }
+        return;
+    }
+
+    if std::env::args().nth(1).as_deref() == Some("at") {
+        let k = std::env::args()
+            .nth(2)
+            .map(|s| s.parse().unwrap())
+            .unwrap_or(0);
+        let history = History::from_input(INPUT, &CrateMover9001).unwrap_or_else(|e| panic!("{e}"));
+        match history.state_at(k) {
+            Some(stacks) => println!("{}tops {}", draw(&stacks), stacks.top_string()),
+            None => println!("there are only {} moves", history.len()),
+        }
+        return;
+    }
+
+    println!("part one answer: {}", part_one(INPUT));
+    println!("part two answer: {}", part_two(INPUT));
 }
@@ -0,0 +1,147 @@
+// Reads the stack drawing using the column positions of the number row, so stacks
+// can have labels of any width and crates labels of any length, then the moves.
+
+use crate::{Move, Moves, Stacks, SupplyError};
+
+// Whitespace-separated tokens of a line with the char columns they span.
+fn tokens(line: &str) -> Vec<(usize, usize, String)> {
+    let mut tokens = vec![];
+    let mut current: Option<(usize, String)> = None;
+    for (i, c) in line.chars().chain([' ']).enumerate() {
+        match (c.is_whitespace(), &mut current) {
+            (false, Some((_, s))) => s.push(c),
+            (false, None) => current = Some((i, c.to_string())),
+            (true, Some(_)) => {
+                let (start, s) = current.take().unwrap();
+                tokens.push((start, i, s));
+            }
+            (true, None) => {}
+        }
+    }
+    tokens
+}
+
+// The `[crate]` labels of one drawing row with the columns they span.
+fn crates(line: &str, n: usize) -> Result<Vec<(usize, usize, String)>, SupplyError> {
+    let error = |message: String| SupplyError::Drawing { line: n, message };
+    let chars = line.chars().collect::<Vec<_>>();
+    let mut crates = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            c if c.is_whitespace() => i += 1,
+            '[' => {
+                let end = (i + 1..chars.len())
+                    .find(|&j| chars[j] == ']')
+                    .ok_or_else(|| error(format!("unclosed crate at column {}", i + 1)))?;
+                if end == i + 1 {
+                    return Err(error(format!("empty crate at column {}", i + 1)));
+                }
+                crates.push((i, end + 1, chars[i + 1..end].iter().collect()));
+                i = end + 1;
+            }
+            c => return Err(error(format!("unexpected {c:?} at column {}", i + 1))),
+        }
+    }
+    Ok(crates)
+}
+
+fn parse_drawing(lines: &[&str]) -> Result<Stacks, SupplyError> {
+    let Some((numbers, rows)) = lines.split_last() else {
+        return Err(SupplyError::Drawing {
+            line: 1,
+            message: "missing stack drawing".to_string(),
+        });
+    };
+    let labels = tokens(numbers);
+    let mut stacks =
+        Stacks::new(labels.iter().map(|(_, _, l)| l.clone()).collect()).ok_or_else(|| {
+            SupplyError::Drawing {
+                line: lines.len(),
+                message: "repeated stack label".to_string(),
+            }
+        })?;
+
+    for (row, line) in rows.iter().enumerate().rev() {
+        let n = row + 1;
+        let height = rows.len() - 1 - row;
+        let mut seen = vec![false; labels.len()];
+        for (start, end, label) in crates(line, n)? {
+            // The stack whose label overlaps the crate, nearest to its centre.
+            let centre = start + end;
+            let i = (0..labels.len())
+                .filter(|&i| labels[i].0 < end && start < labels[i].1)
+                .min_by_key(|&i| (labels[i].0 + labels[i].1).abs_diff(centre))
+                .ok_or_else(|| SupplyError::Drawing {
+                    line: n,
+                    message: format!(
+                        "crate [{label}] at column {} is not above any stack",
+                        start + 1
+                    ),
+                })?;
+            let stack = &labels[i].2;
+            if std::mem::replace(&mut seen[i], true) {
+                return Err(SupplyError::Drawing {
+                    line: n,
+                    message: format!("two crates above stack {stack}"),
+                });
+            }
+            let crates = stacks.get_mut(stack).unwrap();
+            if crates.len() != height {
+                return Err(SupplyError::Drawing {
+                    line: n,
+                    message: format!("crate [{label}] floats above stack {stack}"),
+                });
+            }
+            crates.push(label);
+        }
+    }
+    Ok(stacks)
+}
+
+fn parse_move(line: &str, n: usize, stacks: &Stacks) -> Result<Move, SupplyError> {
+    let error = |message: String| SupplyError::Move { line: n, message };
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    let [count, from, to] = match words[..] {
+        ["move", count, "from", from, "to", to] => [count, from, to],
+        _ => {
+            return Err(error(format!(
+                "expected `move N from A to B`, found {line:?}"
+            )))
+        }
+    };
+    let count = count
+        .parse()
+        .map_err(|_| error(format!("invalid crate count {count:?}")))?;
+    for label in [from, to] {
+        if stacks.position(label).is_none() {
+            return Err(error(format!("unknown stack {label:?}")));
+        }
+    }
+    Ok(Move {
+        count,
+        from: from.to_string(),
+        to: to.to_string(),
+    })
+}
+
+// The drawing runs up to the first blank line and the moves follow it. Moves keep
+// their line numbers for error reporting.
+pub fn parse_input(input: &str) -> Result<(Stacks, Moves), SupplyError> {
+    let lines = input.lines().collect::<Vec<_>>();
+    let blank = lines
+        .iter()
+        .position(|l| l.trim().is_empty())
+        .unwrap_or(lines.len());
+    let stacks = parse_drawing(&lines[..blank])?;
+
+    let moves = lines
+        .iter()
+        .enumerate()
+        .skip(blank + 1)
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| Ok((i + 1, parse_move(line, i + 1, &stacks)?)))
+        .collect::<Result<Moves, _>>()?;
+
+    Ok((stacks, moves))
+}
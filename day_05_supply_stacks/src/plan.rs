@@ -0,0 +1,153 @@
+// Searches for the fewest moves that turn the starting stacks into a target.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::crane::apply;
+use crate::{Crane, Move, Stacks};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Target {
+    // The top crate of every stack, None for an empty stack.
+    Tops(Vec<Option<String>>),
+    // Every crate in place.
+    Layout(Stacks),
+}
+
+impl Target {
+    // Tops written as `Stacks::top_string` does for single-character crates.
+    pub fn tops(s: &str) -> Self {
+        Target::Tops(
+            s.chars()
+                .map(|c| (c != ' ').then(|| c.to_string()))
+                .collect(),
+        )
+    }
+
+    fn reached(&self, stacks: &Stacks) -> bool {
+        match self {
+            Target::Tops(tops) => stacks
+                .iter()
+                .map(|(_, c)| c.last())
+                .eq(tops.iter().map(Option::as_ref)),
+            Target::Layout(layout) => stacks == layout,
+        }
+    }
+
+    // A move changes at most two stacks, so half the stacks that are wrong rounded
+    // up is a lower bound on the moves left.
+    fn lower_bound(&self, stacks: &Stacks) -> usize {
+        let wrong = match self {
+            Target::Tops(tops) => stacks
+                .iter()
+                .zip(tops)
+                .filter(|((_, c), top)| c.last() != top.as_ref())
+                .count(),
+            Target::Layout(layout) => stacks
+                .iter()
+                .zip(layout.iter())
+                .filter(|((_, a), (_, b))| a != b)
+                .count(),
+        };
+        wrong.div_ceil(2)
+    }
+
+    // Whether the target can be reached at all, judging by the crates there are.
+    fn possible(&self, stacks: &Stacks) -> bool {
+        let have = count(stacks.iter().flat_map(|(_, c)| c));
+        match self {
+            Target::Tops(tops) => {
+                let wanted = count(tops.iter().flatten());
+                tops.len() == stacks.len()
+                    && wanted
+                        .iter()
+                        .all(|(c, n)| have.get(c).is_some_and(|h| h >= n))
+            }
+            Target::Layout(layout) => {
+                layout.labels() == stacks.labels()
+                    && count(layout.iter().flat_map(|(_, c)| c)) == have
+            }
+        }
+    }
+}
+
+fn count<'a>(crates: impl Iterator<Item = &'a String>) -> BTreeMap<&'a String, usize> {
+    let mut counts = BTreeMap::new();
+    for c in crates {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    counts
+}
+
+struct Search<'a> {
+    target: &'a Target,
+    crane: &'a dyn Crane,
+    plan: Vec<Move>,
+    // The deepest search that already failed from a state.
+    failed: HashMap<Stacks, usize>,
+}
+
+impl Search<'_> {
+    fn run(&mut self, stacks: &Stacks, depth: usize) -> bool {
+        if self.target.reached(stacks) {
+            return true;
+        }
+        if self.target.lower_bound(stacks) > depth {
+            return false;
+        }
+        if self.failed.get(stacks).is_some_and(|&d| d >= depth) {
+            return false;
+        }
+
+        let labels = stacks.labels();
+        for from in labels {
+            let height = stacks.get(from).unwrap().len();
+            for to in labels.iter().filter(|&to| to != from) {
+                for count in 1..=height {
+                    let m = Move {
+                        count,
+                        from: from.clone(),
+                        to: to.clone(),
+                    };
+                    let mut next = stacks.clone();
+                    apply(&mut next, &m, self.crane).unwrap();
+                    self.plan.push(m);
+                    if self.run(&next, depth - 1) {
+                        return true;
+                    }
+                    self.plan.pop();
+                }
+            }
+        }
+
+        self.failed.insert(stacks.clone(), depth);
+        false
+    }
+}
+
+// The shortest plan of at most `max_moves` moves that reaches the target, found by
+// iterative deepening. Returns None when there is no such plan.
+pub fn plan(
+    stacks: &Stacks,
+    target: &Target,
+    crane: &dyn Crane,
+    max_moves: usize,
+) -> Option<Vec<Move>> {
+    if !target.possible(stacks) {
+        return None;
+    }
+    let mut search = Search {
+        target,
+        crane,
+        plan: vec![],
+        failed: HashMap::new(),
+    };
+    (0..=max_moves).find_map(|depth| {
+        search.plan.clear();
+        search.run(stacks, depth).then(|| search.plan.clone())
+    })
+}
+
+// Writes a plan in the puzzle's move syntax.
+pub fn format_plan(plan: &[Move]) -> String {
+    plan.iter().map(|m| format!("{m}\n")).collect()
+}
@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+
+// Stacks in drawing order, keyed by their printed labels. Each stack holds its
+// crates' labels from the bottom up.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Stacks {
+    labels: Vec<String>,
+    crates: Vec<Vec<String>>,
+    positions: BTreeMap<String, usize>,
+}
+
+impl Stacks {
+    // Empty stacks with the given labels. Returns None if a label repeats.
+    pub fn new(labels: Vec<String>) -> Option<Self> {
+        let positions = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label.clone(), i))
+            .collect::<BTreeMap<_, _>>();
+        (positions.len() == labels.len()).then(|| Self {
+            crates: vec![vec![]; labels.len()],
+            labels,
+            positions,
+        })
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn position(&self, label: &str) -> Option<usize> {
+        self.positions.get(label).copied()
+    }
+
+    pub fn get(&self, label: &str) -> Option<&Vec<String>> {
+        self.crates.get(self.position(label)?)
+    }
+
+    pub fn get_mut(&mut self, label: &str) -> Option<&mut Vec<String>> {
+        let i = self.position(label)?;
+        self.crates.get_mut(i)
+    }
+
+    // Labels and crates of every stack, in drawing order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Vec<String>)> {
+        self.labels.iter().map(String::as_str).zip(&self.crates)
+    }
+
+    pub fn heights(&self) -> Vec<usize> {
+        self.crates.iter().map(Vec::len).collect()
+    }
+
+    // The top crate of every stack, with a space for an empty stack.
+    pub fn top_string(&self) -> String {
+        self.crates
+            .iter()
+            .map(|s| s.last().map_or(" ", String::as_str))
+            .collect()
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use serde_json::Value;
+
+pub const LAST_DAY: u32 = 16;
+
+// Which parts of each day have a solver in this repo that returns a real answer.
+// The `solvers_match_day_crates` test keeps this in step with the day crates.
+const SOLVERS: [(u32, bool, bool); LAST_DAY as usize] = [
+    (1, true, true),
+    (2, true, true),
+    (3, true, true),
+    (4, true, true),
+    (5, true, true),
+    (6, true, true),
+    (7, true, true),
+    (8, true, true),
+    (9, true, true),
+    (10, true, true),
+    (11, true, true),
+    (12, true, true),
+    (13, true, true),
+    (14, true, true),
+    (15, true, true),
+    (16, false, false),
+];
+
+pub fn has_solver(day: u32, part: u32) -> bool {
+    SOLVERS
+        .iter()
+        .find(|&&(d, _, _)| d == day)
+        .map(|&(_, one, two)| if part == 1 { one } else { two })
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub id: u64,
+    pub name: String,
+    // day -> [part one, part two] star timestamps
+    pub stars: BTreeMap<u32, [Option<i64>; 2]>,
+}
+
+impl Member {
+    pub fn star(&self, day: u32, part: u32) -> Option<i64> {
+        let i = (part as usize).checked_sub(1)?;
+        self.stars.get(&day).and_then(|s| *s.get(i)?)
+    }
+
+    fn from_value(id: &str, value: &Value) -> Self {
+        let id = value["id"]
+            .as_u64()
+            .unwrap_or_else(|| id.parse().unwrap_or(0));
+        let name = match value["name"].as_str() {
+            Some(name) => name.to_string(),
+            None => format!("(anonymous user #{id})"),
+        };
+
+        let mut stars = BTreeMap::new();
+        if let Some(days) = value["completion_day_level"].as_object() {
+            for (day, parts) in days {
+                let day = match day.parse::<u32>() {
+                    Ok(day) => day,
+                    Err(_) => continue,
+                };
+                let entry: &mut [Option<i64>; 2] = stars.entry(day).or_default();
+                for part in 1..=2 {
+                    entry[part - 1] = parts[part.to_string()]["get_star_ts"].as_i64();
+                }
+            }
+        }
+
+        Member { id, name, stars }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayStars {
+    pub day: u32,
+    pub part_one: Option<i64>,
+    pub part_two: Option<i64>,
+}
+
+impl DayStars {
+    pub fn delta(&self) -> Option<i64> {
+        Some(self.part_two? - self.part_one?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingSolver {
+    pub member: String,
+    pub day: u32,
+    pub part: u32,
+}
+
+pub fn parse_leaderboard(input: &str) -> serde_json::Result<Vec<Member>> {
+    let value: Value = serde_json::from_str(input)?;
+    let mut members = value["members"]
+        .as_object()
+        .map(|m| {
+            m.iter()
+                .map(|(id, v)| Member::from_value(id, v))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    members.sort_by_key(|m| m.id);
+    Ok(members)
+}
+
+pub fn day_stars(member: &Member) -> Vec<DayStars> {
+    (1..=LAST_DAY)
+        .map(|day| DayStars {
+            day,
+            part_one: member.star(day, 1),
+            part_two: member.star(day, 2),
+        })
+        .collect()
+}
+
+// Local score after each day, using the leaderboard's own rule: the first member to
+// get a star scores one point per member, the next one point fewer, and so on.
+pub fn ranking_over_time(members: &[Member]) -> Vec<(u32, Vec<(String, u64)>)> {
+    let n = members.len() as u64;
+    let mut scores = vec![0u64; members.len()];
+    let mut ranking = Vec::new();
+
+    for day in 1..=LAST_DAY {
+        for part in 1..=2 {
+            let mut finishers = members
+                .iter()
+                .enumerate()
+                .filter_map(|(i, m)| m.star(day, part).map(|ts| (ts, m.id, i)))
+                .collect::<Vec<_>>();
+            finishers.sort();
+            for (place, &(_, _, i)) in finishers.iter().enumerate() {
+                scores[i] += n - place as u64;
+            }
+        }
+
+        let mut standing = members
+            .iter()
+            .zip(scores.iter())
+            .map(|(m, &score)| (m.id, m.name.clone(), score))
+            .collect::<Vec<_>>();
+        standing.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+        ranking.push((
+            day,
+            standing
+                .into_iter()
+                .map(|(_, name, score)| (name, score))
+                .collect(),
+        ));
+    }
+
+    ranking
+}
+
+pub fn missing_solvers(members: &[Member]) -> Vec<MissingSolver> {
+    let mut missing = Vec::new();
+    for m in members {
+        for day in 1..=LAST_DAY {
+            for part in 1..=2 {
+                if m.star(day, part).is_some() && !has_solver(day, part) {
+                    missing.push(MissingSolver {
+                        member: m.name.clone(),
+                        day,
+                        part,
+                    });
+                }
+            }
+        }
+    }
+    missing
+}
+
+// Stars earned on days 1 to LAST_DAY.
+pub fn star_count(member: &Member) -> usize {
+    day_stars(member)
+        .iter()
+        .map(|d| d.part_one.is_some() as usize + d.part_two.is_some() as usize)
+        .sum()
+}
+
+// A Unix timestamp as a UTC date and time, e.g. `2022-12-01 05:03:20`.
+pub fn format_timestamp(ts: i64) -> String {
+    let (days, seconds) = (ts.div_euclid(86400), ts.rem_euclid(86400));
+    // Civil date from days since 1970-01-01, counting in 400-year eras from March 1st.
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+fn format_duration(seconds: i64) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+pub fn report(input: &str) -> serde_json::Result<String> {
+    let members = parse_leaderboard(input)?;
+    let mut out = String::new();
+
+    for m in &members {
+        writeln!(out, "{} ({} stars)", m.name, star_count(m)).unwrap();
+        writeln!(
+            out,
+            "  day  part one             part two             delta"
+        )
+        .unwrap();
+        for d in day_stars(m) {
+            if d.part_one.is_none() && d.part_two.is_none() {
+                continue;
+            }
+            let ts = |t: Option<i64>| t.map(format_timestamp).unwrap_or_else(|| "-".to_string());
+            let delta = d
+                .delta()
+                .map(format_duration)
+                .unwrap_or_else(|| "-".to_string());
+            writeln!(
+                out,
+                "  {:>3}  {:<19}  {:<19}  {}",
+                d.day,
+                ts(d.part_one),
+                ts(d.part_two),
+                delta
+            )
+            .unwrap();
+        }
+    }
+
+    writeln!(out, "ranking over time:").unwrap();
+    for (day, standing) in ranking_over_time(&members) {
+        let standing = standing
+            .iter()
+            .map(|(name, score)| format!("{name} {score}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(out, "  day {day:>2}: {standing}").unwrap();
+    }
+
+    let missing = missing_solvers(&members);
+    if !missing.is_empty() {
+        writeln!(out, "stars without a working solver in this repo:").unwrap();
+        for ms in missing {
+            writeln!(out, "  {} day {} part {}", ms.member, ms.day, ms.part).unwrap();
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../data/example.json");
+
+    #[test]
+    fn parse_works() {
+        let members = parse_leaderboard(EXAMPLE).unwrap();
+        assert_eq!(members.len(), 3);
+        assert_eq!(members[2].name, "(anonymous user #1003)");
+        assert!(members[0].star(1, 1).is_some());
+        assert_eq!(members[0].star(1, 0), None);
+        assert_eq!(members[0].star(1, 3), None);
+        assert!(members[1].star(17, 2).is_some());
+    }
+
+    #[test]
+    fn delta_works() {
+        let members = parse_leaderboard(EXAMPLE).unwrap();
+        let days = day_stars(&members[0]);
+        assert_eq!(days.len(), LAST_DAY as usize);
+        assert_eq!(days[0].delta(), Some(300));
+        assert_eq!(days[2].delta(), None);
+        assert_eq!(format_duration(days[1].delta().unwrap()), "0:10:00");
+    }
+
+    #[test]
+    fn stars_after_last_day_are_not_counted() {
+        let members = parse_leaderboard(EXAMPLE).unwrap();
+        assert_eq!(star_count(&members[0]), 6);
+        assert_eq!(star_count(&members[1]), 6);
+        let report = report(EXAMPLE).unwrap();
+        assert!(report.contains("ann (6 stars)"));
+        assert!(!report.contains("   17  "));
+    }
+
+    #[test]
+    fn timestamps_are_utc() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+        assert_eq!(format_timestamp(1669871000), "2022-12-01 05:03:20");
+        assert_eq!(format_timestamp(951782400), "2000-02-29 00:00:00");
+        assert_eq!(format_timestamp(-1), "1969-12-31 23:59:59");
+        assert!(report(EXAMPLE)
+            .unwrap()
+            .contains("    1  2022-12-01 05:03:20  2022-12-01 05:08:20  0:05:00"));
+    }
+
+    #[test]
+    fn ranking_works() {
+        let members = parse_leaderboard(EXAMPLE).unwrap();
+        let ranking = ranking_over_time(&members);
+        assert_eq!(ranking.len(), LAST_DAY as usize);
+        assert_eq!(
+            ranking[0].1,
+            vec![
+                ("joy".to_string(), 5),
+                ("ann".to_string(), 5),
+                ("(anonymous user #1003)".to_string(), 0)
+            ]
+        );
+        assert_eq!(ranking[2].1[0], ("ann".to_string(), 16));
+        assert_eq!(ranking[15].1[1], ("joy".to_string(), 15));
+    }
+
+    #[test]
+    fn missing_solvers_work() {
+        let members = parse_leaderboard(EXAMPLE).unwrap();
+        assert_eq!(
+            missing_solvers(&members),
+            vec![MissingSolver {
+                member: "joy".to_string(),
+                day: 16,
+                part: 1
+            }]
+        );
+    }
+
+    // A part has a working solver when its day crate defines `part_one` or `part_two`
+    // and the function does not just fall through to a placeholder `0`.
+    fn solver_in_repo(day: u32, part: u32) -> bool {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let prefix = format!("day_{day:02}_");
+        let Some(dir) = std::fs::read_dir(&root)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .find(|p| {
+                p.file_name()
+                    .unwrap()
+                    .to_str()
+                    .unwrap()
+                    .starts_with(&prefix)
+            })
+        else {
+            return false;
+        };
+        let lib = std::fs::read_to_string(dir.join("src/lib.rs")).unwrap();
+        let name = if part == 1 { "part_one" } else { "part_two" };
+        let Some(start) = lib.find(&format!("pub fn {name}(")) else {
+            return false;
+        };
+        let body = &lib[start..start + lib[start..].find("\n}").unwrap()];
+        let last = body.lines().rev().map(str::trim).find(|l| !l.is_empty());
+        (last != Some("0") || body.contains("return "))
+            && !body.contains("todo!")
+            && !body.contains("unimplemented!")
+    }
+
+    #[test]
+    fn solvers_match_day_crates() {
+        for day in 1..=LAST_DAY {
+            for part in 1..=2 {
+                assert_eq!(
+                    has_solver(day, part),
+                    solver_in_repo(day, part),
+                    "day {day} part {part}"
+                );
+            }
+        }
+    }
+}
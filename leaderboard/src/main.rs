@@ -0,0 +1,21 @@
+use leaderboard::report;
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "data/leaderboard.json".to_string());
+    let input = match std::fs::read_to_string(&path) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("cannot read {path}: {e}");
+            std::process::exit(1);
+        }
+    };
+    match report(&input) {
+        Ok(report) => print!("{report}"),
+        Err(e) => {
+            eprintln!("invalid leaderboard {path}: {e}");
+            std::process::exit(1);
+        }
+    }
+}